@@ -0,0 +1,342 @@
+//! Parser for the CSS subset accepted by `css!`.
+
+/// How to convert the value of a CSS property.
+enum Kind {
+    /// One of the listed keywords, converted into a variant of the given enum.
+    Enum(&'static str, &'static [&'static str]),
+    /// A single length, converted into a `Val`.
+    Val,
+    /// 1 to 4 lengths, converted into a `UiRect`, in the CSS order.
+    Rect,
+    /// A number, converted into a `f32`.
+    Number,
+    /// A number or a `width / height` ratio, converted into a `Option<f32>`.
+    Ratio,
+    /// The `gap: row column` shorthand.
+    Gap,
+    /// The `flex: grow shrink basis` shorthand.
+    Flex,
+}
+
+const ALIGN: &[&str] = &[
+    "start",
+    "end",
+    "flex-start",
+    "flex-end",
+    "center",
+    "baseline",
+    "stretch",
+];
+const ALIGN_SELF: &[&str] = &[
+    "auto",
+    "start",
+    "end",
+    "flex-start",
+    "flex-end",
+    "center",
+    "baseline",
+    "stretch",
+];
+const JUSTIFY_ITEMS: &[&str] = &["start", "end", "center", "baseline", "stretch"];
+const JUSTIFY_SELF: &[&str] = &["auto", "start", "end", "center", "baseline", "stretch"];
+const ALIGN_CONTENT: &[&str] = &[
+    "start",
+    "end",
+    "flex-start",
+    "flex-end",
+    "center",
+    "stretch",
+    "space-between",
+    "space-around",
+    "space-evenly",
+];
+const JUSTIFY: &[&str] = &[
+    "start",
+    "end",
+    "flex-start",
+    "flex-end",
+    "center",
+    "stretch",
+    "space-between",
+    "space-around",
+    "space-evenly",
+];
+
+/// Supported CSS properties, with the name of the `Style` field they set.
+const PROPERTIES: &[(&str, &str, Kind)] = &[
+    (
+        "display",
+        "display",
+        Kind::Enum("Display", &["flex", "grid", "none"]),
+    ),
+    (
+        "position",
+        "position_type",
+        Kind::Enum("PositionType", &["relative", "absolute"]),
+    ),
+    (
+        "flex-direction",
+        "flex_direction",
+        Kind::Enum(
+            "FlexDirection",
+            &["row", "column", "row-reverse", "column-reverse"],
+        ),
+    ),
+    (
+        "flex-wrap",
+        "flex_wrap",
+        Kind::Enum("FlexWrap", &["nowrap", "wrap", "wrap-reverse"]),
+    ),
+    (
+        "align-items",
+        "align_items",
+        Kind::Enum("AlignItems", ALIGN),
+    ),
+    (
+        "align-self",
+        "align_self",
+        Kind::Enum("AlignSelf", ALIGN_SELF),
+    ),
+    (
+        "align-content",
+        "align_content",
+        Kind::Enum("AlignContent", ALIGN_CONTENT),
+    ),
+    (
+        "justify-content",
+        "justify_content",
+        Kind::Enum("JustifyContent", JUSTIFY),
+    ),
+    (
+        "justify-items",
+        "justify_items",
+        Kind::Enum("JustifyItems", JUSTIFY_ITEMS),
+    ),
+    (
+        "justify-self",
+        "justify_self",
+        Kind::Enum("JustifySelf", JUSTIFY_SELF),
+    ),
+    ("width", "width", Kind::Val),
+    ("height", "height", Kind::Val),
+    ("min-width", "min_width", Kind::Val),
+    ("min-height", "min_height", Kind::Val),
+    ("max-width", "max_width", Kind::Val),
+    ("max-height", "max_height", Kind::Val),
+    ("flex-basis", "flex_basis", Kind::Val),
+    ("left", "left", Kind::Val),
+    ("right", "right", Kind::Val),
+    ("top", "top", Kind::Val),
+    ("bottom", "bottom", Kind::Val),
+    ("row-gap", "row_gap", Kind::Val),
+    ("column-gap", "column_gap", Kind::Val),
+    ("margin", "margin", Kind::Rect),
+    ("padding", "padding", Kind::Rect),
+    ("border-width", "border", Kind::Rect),
+    ("flex-grow", "flex_grow", Kind::Number),
+    ("flex-shrink", "flex_shrink", Kind::Number),
+    ("aspect-ratio", "aspect_ratio", Kind::Ratio),
+    ("gap", "row_gap", Kind::Gap),
+    ("flex", "flex_grow", Kind::Flex),
+];
+
+/// The names of the supported CSS properties.
+pub(crate) fn property_names() -> impl Iterator<Item = &'static str> {
+    PROPERTIES.iter().map(|(name, _, _)| *name)
+}
+
+/// The Rust source of a `f32` literal, `inf` and `NaN` have none.
+fn float(property: &str, value: f32) -> Result<String, String> {
+    if !value.is_finite() {
+        return Err(format!("`{property}` value out of range"));
+    }
+    Ok(format!("{value:?}"))
+}
+
+fn number(value: &str) -> Option<f32> {
+    value.parse().ok()
+}
+
+/// `8px` ⇒ `bevy::ui::Val::Px(8.0)`
+fn length(property: &str, value: &str) -> Result<String, String> {
+    const UNITS: &[(&str, &str)] = &[
+        ("px", "Px"),
+        ("%", "Percent"),
+        ("vmin", "VMin"),
+        ("vmax", "VMax"),
+        ("vw", "Vw"),
+        ("vh", "Vh"),
+    ];
+    if value == "auto" {
+        return Ok("bevy::ui::Val::Auto".to_owned());
+    }
+    if number(value) == Some(0.0) {
+        return Ok("bevy::ui::Val::Px(0.0)".to_owned());
+    }
+    UNITS
+        .iter()
+        .find_map(|(unit, variant)| {
+            let value = number(value.strip_suffix(unit)?)?;
+            Some(float(property, value).map(|value| format!("bevy::ui::Val::{variant}({value})")))
+        })
+        .ok_or_else(|| {
+            format!(
+                "invalid length `{value}` for `{property}`, expected a number followed by \
+                `px`, `%`, `vw`, `vh`, `vmin` or `vmax`, `auto` or `0`"
+            )
+        })?
+}
+
+/// `flex-start` ⇒ `FlexStart`, `nowrap` ⇒ `NoWrap`
+fn variant(keyword: &str) -> String {
+    if keyword == "nowrap" {
+        return "NoWrap".to_owned();
+    }
+    keyword
+        .split('-')
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+        })
+        .collect()
+}
+
+/// The `(field, value)` pairs of the `Style` fields set by `property: value`.
+pub(crate) fn declaration(
+    property: &str,
+    value: &str,
+) -> Result<Vec<(&'static str, String)>, String> {
+    let Some((_, field, kind)) = PROPERTIES.iter().find(|(name, _, _)| *name == property) else {
+        return Err(format!("css! doesn't support the `{property}` property"));
+    };
+    let values: Vec<&str> = value.split_whitespace().collect();
+    let single = || match values[..] {
+        [value] => Ok(value),
+        _ => Err(format!(
+            "`{property}` expects a single value, got `{value}`"
+        )),
+    };
+    let invalid_number = || format!("invalid number `{value}` for `{property}`");
+    let declarations = match kind {
+        Kind::Enum(name, keywords) => {
+            let keyword = single()?;
+            if !keywords.contains(&keyword) {
+                let expected: Vec<String> = keywords.iter().map(|k| format!("`{k}`")).collect();
+                return Err(format!(
+                    "invalid value `{keyword}` for `{property}`, expected one of {}",
+                    expected.join(", ")
+                ));
+            }
+            vec![(*field, format!("bevy::ui::{name}::{}", variant(keyword)))]
+        }
+        Kind::Val => vec![(*field, length(property, single()?)?)],
+        Kind::Rect => {
+            let sides: Vec<String> = values
+                .iter()
+                .map(|value| length(property, value))
+                .collect::<Result<_, _>>()?;
+            let (top, right, bottom, left) = match &sides[..] {
+                [all] => (all, all, all, all),
+                [vertical, horizontal] => (vertical, horizontal, vertical, horizontal),
+                [top, horizontal, bottom] => (top, horizontal, bottom, horizontal),
+                [top, right, bottom, left] => (top, right, bottom, left),
+                _ => return Err(format!("`{property}` expects 1, 2, 3 or 4 values")),
+            };
+            let rect = format!(
+                "bevy::ui::UiRect {{ left: {left}, right: {right}, top: {top}, bottom: {bottom} }}"
+            );
+            vec![(*field, rect)]
+        }
+        Kind::Number => vec![(
+            *field,
+            float(property, number(single()?).ok_or_else(invalid_number)?)?,
+        )],
+        Kind::Ratio => {
+            let ratio = match value.split_once('/') {
+                Some((width, height)) => number(width.trim())
+                    .zip(number(height.trim()))
+                    .map(|(w, h)| w / h),
+                None => number(single()?),
+            };
+            vec![(
+                *field,
+                format!(
+                    "Some({})",
+                    float(property, ratio.ok_or_else(invalid_number)?)?
+                ),
+            )]
+        }
+        Kind::Gap => {
+            let (row, column) = match values[..] {
+                [both] => (both, both),
+                [row, column] => (row, column),
+                _ => return Err(format!("`{property}` expects 1 or 2 values")),
+            };
+            vec![
+                ("row_gap", length(property, row)?),
+                ("column_gap", length(property, column)?),
+            ]
+        }
+        Kind::Flex => {
+            let (grow, shrink, basis) = match values[..] {
+                ["none"] => ("0", "0", "auto"),
+                ["auto"] => ("1", "1", "auto"),
+                [grow] => (grow, "1", "0"),
+                [grow, shrink, basis] => (grow, shrink, basis),
+                _ => return Err(format!("`{property}` expects 1 or 3 values")),
+            };
+            vec![
+                (
+                    "flex_grow",
+                    float(property, number(grow).ok_or_else(invalid_number)?)?,
+                ),
+                (
+                    "flex_shrink",
+                    float(property, number(shrink).ok_or_else(invalid_number)?)?,
+                ),
+                ("flex_basis", length(property, basis)?),
+            ]
+        }
+    };
+    Ok(declarations)
+}
+
+/// `css` without its `/* comments */`.
+pub(crate) fn strip_comments(mut css: &str) -> String {
+    let mut stripped = String::with_capacity(css.len());
+    while let Some((before, after)) = css.split_once("/*") {
+        stripped.push_str(before);
+        css = after.split_once("*/").map_or("", |(_, after)| after);
+    }
+    stripped.push_str(css);
+    stripped
+}
+
+/// The content of the string literal `literal`, `None` if it isn't one.
+pub(crate) fn string_value(literal: &str) -> Option<String> {
+    if let Some(raw) = literal.strip_prefix('r') {
+        let raw = raw.trim_matches('#');
+        return Some(raw.strip_prefix('"')?.strip_suffix('"')?.to_owned());
+    }
+    let escaped = literal.strip_prefix('"')?.strip_suffix('"')?;
+    let mut value = String::with_capacity(escaped.len());
+    let mut chars = escaped.chars().peekable();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            value.push(char);
+            continue;
+        }
+        match chars.next()? {
+            'n' => value.push('\n'),
+            't' => value.push('\t'),
+            'r' => value.push('\r'),
+            '\n' => while chars.next_if(|c| c.is_whitespace()).is_some() {},
+            other => value.push(other),
+        }
+    }
+    Some(value)
+}
@@ -0,0 +1,541 @@
+//! Procedural macros used by `bevy-ui-build-macros`, do not use directly.
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+mod css;
+mod tree;
+
+/// Fields of `bevy::ui::Style` in all bevy versions from 0.9 to 0.14.
+///
+/// `Style` was renamed `Node` in bevy 0.15, so this list won't change.
+const STYLE_FIELDS: &[&str] = &[
+    "align_content",
+    "align_items",
+    "align_self",
+    "aspect_ratio",
+    "border",
+    "bottom",
+    "column_gap",
+    "direction",
+    "display",
+    "flex_basis",
+    "flex_direction",
+    "flex_grow",
+    "flex_shrink",
+    "flex_wrap",
+    "gap",
+    "grid_auto_columns",
+    "grid_auto_flow",
+    "grid_auto_rows",
+    "grid_column",
+    "grid_row",
+    "grid_template_columns",
+    "grid_template_rows",
+    "height",
+    "justify_content",
+    "justify_items",
+    "justify_self",
+    "left",
+    "margin",
+    "max_height",
+    "max_size",
+    "max_width",
+    "min_height",
+    "min_size",
+    "min_width",
+    "overflow",
+    "padding",
+    "position",
+    "position_type",
+    "right",
+    "row_gap",
+    "size",
+    "top",
+    "width",
+];
+
+/// Fields of `bevy::ui::Style` of type `Val`.
+const VAL_FIELDS: &[&str] = &[
+    "bottom",
+    "column_gap",
+    "flex_basis",
+    "height",
+    "left",
+    "max_height",
+    "max_width",
+    "min_height",
+    "min_width",
+    "right",
+    "row_gap",
+    "top",
+    "width",
+];
+
+/// Fields of `bevy::ui::Style` of type `UiRect`.
+const RECT_FIELDS: &[&str] = &["border", "margin", "padding", "position"];
+
+/// Units and keywords accepted by `unit!`.
+const UNITS: &[&str] = &["px", "pct", "vw", "vh", "vmin", "vmax"];
+const UNIT_KEYWORDS: &[&str] = &["auto", "undefined", "zero", "full", "0"];
+
+/// Whether `value` is written in the `unit!` syntax, such as `10 px` or `auto`.
+fn is_unit_syntax(value: &[TokenTree]) -> bool {
+    match value {
+        [TokenTree::Group(group)] if group.delimiter() == Delimiter::None => {
+            let inner: Vec<TokenTree> = group.stream().into_iter().collect();
+            is_unit_syntax(&inner)
+        }
+        [single] => UNIT_KEYWORDS.contains(&single.to_string().as_str()),
+        [.., TokenTree::Ident(unit)] => UNITS.contains(&unit.to_string().as_str()),
+        _ => false,
+    }
+}
+
+/// `value` wrapped in `macro_name!(value)`, if it is written in the `unit!` syntax.
+fn wrap_value(field: &str, value: Vec<TokenTree>) -> Vec<TokenTree> {
+    let macro_name = if VAL_FIELDS.contains(&field) {
+        "unit"
+    } else if RECT_FIELDS.contains(&field) {
+        "rect"
+    } else {
+        return value;
+    };
+    if !is_unit_syntax(&value) {
+        return value;
+    }
+    let span = value[0].span();
+    let mut bang = Punct::new('!', Spacing::Alone);
+    bang.set_span(span);
+    let mut args = Group::new(Delimiter::Parenthesis, value.into_iter().collect());
+    args.set_span(span);
+    vec![
+        Ident::new(macro_name, span).into(),
+        bang.into(),
+        args.into(),
+    ]
+}
+
+/// Levenshtein distance between `a` and `b`.
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a_char != *b_char);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// The name in `known` closest to `name`, if any is close enough.
+fn suggestion(name: &str, known: impl IntoIterator<Item = &'static str>) -> Option<&'static str> {
+    let max_distance = (name.len() / 3).max(1);
+    known
+        .into_iter()
+        .map(|known| (distance(name, known), known))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, known)| known)
+}
+
+/// `compile_error!("message");` pointing to `span`.
+fn compile_error(message: &str, span: Span) -> impl Iterator<Item = TokenTree> {
+    let mut message = Literal::string(message);
+    message.set_span(span);
+    let mut bang = Punct::new('!', Spacing::Alone);
+    bang.set_span(span);
+    let mut semicolon = Punct::new(';', Spacing::Alone);
+    semicolon.set_span(span);
+    let mut args = Group::new(Delimiter::Parenthesis, TokenTree::from(message).into());
+    args.set_span(span);
+    [
+        Ident::new("compile_error", span).into(),
+        bang.into(),
+        args.into(),
+        semicolon.into(),
+    ]
+    .into_iter()
+}
+
+/// Builds a `bevy::ui::Style` literal, checking that the fields exist.
+///
+/// # Syntax
+/// `__style!((default) field1: value1, field2: value2,)` ⇒
+/// `bevy::ui::Style { field1: value1, field2: value2, .. default }`
+///
+/// `if (condition) { entries1 } else { entries2 },` entries are applied in
+/// order after the other fields, with `style! { ..style, entries1 }`.
+///
+/// With a first `__patch: krate,` entry and the `__patch` default, builds a
+/// `krate::StylePatch` of the fields instead, see `style_patch!`.
+#[doc(hidden)]
+#[proc_macro]
+pub fn __style(input: TokenStream) -> TokenStream {
+    let mut input = input.into_iter().peekable();
+    let default = match input.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => group,
+        _ => panic!("__style! expects the default style between parenthesis"),
+    };
+    let mut errors = TokenStream::new();
+    let mut fields = TokenStream::new();
+    let mut conditionals = TokenStream::new();
+    let mut patched = TokenStream::new();
+    let mut patch = None;
+    let binding = Ident::new("style", Span::mixed_site());
+    while input.peek().is_some() {
+        let entry: Vec<TokenTree> = input
+            .by_ref()
+            .take_while(|token| !matches!(token, TokenTree::Punct(p) if p.as_char() == ','))
+            .collect();
+        if let Some((condition, then, otherwise)) = conditional(&entry) {
+            let update = |entries| match &patch {
+                Some(_) => merge(&binding, entries),
+                None => update(&binding, entries),
+            };
+            conditionals.extend("if".parse::<TokenStream>().unwrap());
+            conditionals.extend(condition.stream());
+            conditionals.extend([
+                TokenTree::from(update(then.stream())),
+                Ident::new("else", Span::call_site()).into(),
+                update(otherwise.stream()).into(),
+            ]);
+            continue;
+        }
+        let mut entry = entry.into_iter();
+        let (Some(TokenTree::Ident(field)), Some(colon)) = (entry.next(), entry.next()) else {
+            panic!("__style! expects `field: value` entries");
+        };
+        let name = field.to_string();
+        if name == "__patch" {
+            if !is_patch_default(&default) {
+                let message =
+                    "`style_patch!` only sets fields, presets and `..base` are not supported";
+                errors.extend(compile_error(message, Span::call_site()));
+            }
+            patch = Some(entry.collect::<TokenStream>());
+            continue;
+        }
+        if !STYLE_FIELDS.contains(&name.as_str()) {
+            let message = match suggestion(&name, STYLE_FIELDS.iter().copied()) {
+                Some(known) => format!("`Style` has no field `{name}`, did you mean `{known}`?"),
+                None => format!("`Style` has no field `{name}`"),
+            };
+            errors.extend(compile_error(&message, field.span()));
+            continue;
+        }
+        if patch.is_some() {
+            patched.extend(patch_field(&binding, &field));
+        }
+        fields.extend([TokenTree::from(field), colon]);
+        fields.extend(wrap_value(&name, entry.collect()));
+        fields.extend([TokenTree::from(Punct::new(',', Spacing::Alone))]);
+    }
+    let default = match patch {
+        Some(_) => "Default::default()".parse().unwrap(),
+        None => TokenStream::from(TokenTree::from(default)),
+    };
+    fields.extend([
+        TokenTree::from(Punct::new('.', Spacing::Joint)),
+        Punct::new('.', Spacing::Alone).into(),
+    ]);
+    fields.extend(default);
+    let mut block = errors;
+    if let Some(krate) = patch {
+        // `let mut style = krate::StylePatch::new(bevy::ui::Style { .. });`
+        block.extend("let mut".parse::<TokenStream>().unwrap());
+        block.extend([
+            TokenTree::from(binding.clone()),
+            Punct::new('=', Spacing::Alone).into(),
+        ]);
+        block.extend(krate);
+        block.extend(":: StylePatch :: new".parse::<TokenStream>().unwrap());
+        let mut values: TokenStream = "bevy::ui::Style".parse().unwrap();
+        values.extend([TokenTree::from(Group::new(Delimiter::Brace, fields))]);
+        block.extend([
+            TokenTree::from(Group::new(Delimiter::Parenthesis, values)),
+            Punct::new(';', Spacing::Alone).into(),
+        ]);
+        block.extend(patched);
+        block.extend(conditionals);
+        block.extend([TokenTree::from(binding)]);
+        return TokenTree::from(Group::new(Delimiter::Brace, block)).into();
+    }
+    if !conditionals.is_empty() {
+        block.extend("let mut".parse::<TokenStream>().unwrap());
+        block.extend([
+            TokenTree::from(binding.clone()),
+            Punct::new('=', Spacing::Alone).into(),
+        ]);
+    }
+    block.extend("bevy::ui::Style".parse::<TokenStream>().unwrap());
+    block.extend([TokenTree::from(Group::new(Delimiter::Brace, fields))]);
+    if !conditionals.is_empty() {
+        block.extend([TokenTree::from(Punct::new(';', Spacing::Alone))]);
+        block.extend(conditionals);
+        block.extend([TokenTree::from(binding)]);
+    }
+    TokenTree::from(Group::new(Delimiter::Brace, block)).into()
+}
+
+/// The condition and branches of an `if (condition) { then } else { otherwise }` entry.
+fn conditional(entry: &[TokenTree]) -> Option<(&Group, &Group, &Group)> {
+    match entry {
+        [TokenTree::Ident(keyword), TokenTree::Group(condition), TokenTree::Group(then), _, TokenTree::Group(otherwise)]
+            if keyword.to_string() == "if" =>
+        {
+            Some((condition, then, otherwise))
+        }
+        _ => None,
+    }
+}
+
+/// `{ binding = style! { ..binding, entries }; }`
+fn update(binding: &Ident, entries: TokenStream) -> Group {
+    let mut patch: TokenStream = "..".parse().unwrap();
+    patch.extend([
+        TokenTree::from(binding.clone()),
+        Punct::new(',', Spacing::Alone).into(),
+    ]);
+    patch.extend(entries);
+    let mut statement = TokenStream::new();
+    statement.extend([
+        TokenTree::from(binding.clone()),
+        Punct::new('=', Spacing::Alone).into(),
+        Ident::new("style", Span::call_site()).into(),
+        Punct::new('!', Spacing::Alone).into(),
+        Group::new(Delimiter::Brace, patch).into(),
+        Punct::new(';', Spacing::Alone).into(),
+    ]);
+    Group::new(Delimiter::Brace, statement)
+}
+
+/// Whether the default style of `__style!` is the `__patch` of `style_patch!`,
+/// rather than a preset or a `..base`.
+fn is_patch_default(default: &Group) -> bool {
+    let mut tokens: Vec<TokenTree> = default.stream().into_iter().collect();
+    while let [TokenTree::Group(group)] = &tokens[..] {
+        if group.delimiter() != Delimiter::None {
+            break;
+        }
+        tokens = group.stream().into_iter().collect();
+    }
+    matches!(&tokens[..], [TokenTree::Ident(ident)] if ident.to_string() == "__patch")
+}
+
+/// `binding.patch_field("field", |style, values| style.field = values.field.clone());`
+fn patch_field(binding: &Ident, field: &Ident) -> TokenStream {
+    let field = Ident::new(&field.to_string(), Span::call_site());
+    let copy =
+        format!("|style, values| style.{field} = ::core::clone::Clone::clone(&values.{field})");
+    let mut arguments = TokenStream::from(TokenTree::from(Literal::string(&field.to_string())));
+    arguments.extend([TokenTree::from(Punct::new(',', Spacing::Alone))]);
+    arguments.extend(copy.parse::<TokenStream>().unwrap());
+    let mut statement = TokenStream::from(TokenTree::from(binding.clone()));
+    statement.extend(". patch_field".parse::<TokenStream>().unwrap());
+    statement.extend([
+        TokenTree::from(Group::new(Delimiter::Parenthesis, arguments)),
+        Punct::new(';', Spacing::Alone).into(),
+    ]);
+    statement
+}
+
+/// `{ binding.merge(&style_patch! { entries }); }`
+fn merge(binding: &Ident, entries: TokenStream) -> Group {
+    let mut patch: TokenStream = "& style_patch!".parse().unwrap();
+    patch.extend([TokenTree::from(Group::new(Delimiter::Brace, entries))]);
+    let mut statement = TokenStream::from(TokenTree::from(binding.clone()));
+    statement.extend(". merge".parse::<TokenStream>().unwrap());
+    statement.extend([
+        TokenTree::from(Group::new(Delimiter::Parenthesis, patch)),
+        Punct::new(';', Spacing::Alone).into(),
+    ]);
+    Group::new(Delimiter::Brace, statement)
+}
+
+/// Accesses a field of a `build_ui!` preset, a missing field is reported at
+/// the preset rather than in the macro internals.
+///
+/// # Syntax
+/// `__field!(bundle preset field)` ⇒ `bundle.field`, where `field` has the
+/// span of `preset`.
+#[doc(hidden)]
+#[proc_macro]
+pub fn __field(input: TokenStream) -> TokenStream {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    let [bundle, TokenTree::Ident(preset), TokenTree::Ident(field)] = &tokens[..] else {
+        panic!("__field! expects `bundle preset field`");
+    };
+    let mut dot = Punct::new('.', Spacing::Alone);
+    dot.set_span(preset.span());
+    [
+        bundle.clone(),
+        dot.into(),
+        Ident::new(&field.to_string(), preset.span()).into(),
+    ]
+    .into_iter()
+    .collect()
+}
+
+/// Spawns each child of a `build_ui!` children list, as a separate statement
+/// rather than through a macro recursion per sibling.
+///
+/// # Syntax
+/// `__siblings!((cmds) opts (child1, child2))` ⇒
+/// `build_ui!(@child opts (cmds) child1); build_ui!(@child opts (cmds) child2);`
+#[doc(hidden)]
+#[proc_macro]
+pub fn __siblings(input: TokenStream) -> TokenStream {
+    let mut input = input.into_iter();
+    let (Some(TokenTree::Group(cmds)), Some(opts), Some(TokenTree::Group(list))) =
+        (input.next(), input.next(), input.next())
+    else {
+        panic!("__siblings! expects `(cmds) opts (children)`");
+    };
+    tree::siblings(cmds, opts, list)
+}
+
+/// Collects the `as name` of a `build_ui!` tree, so that they are declared
+/// before spawning it.
+///
+/// # Syntax
+/// `__names!(declaration (tree))` ⇒ `build_ui!(@declare [name1 name2] declaration)`
+#[doc(hidden)]
+#[proc_macro]
+pub fn __names(input: TokenStream) -> TokenStream {
+    let mut input = input.into_iter();
+    let (Some(declaration), Some(TokenTree::Group(tree))) = (input.next(), input.next()) else {
+        panic!("__names! expects `declaration (tree)`");
+    };
+    let mut names = Vec::new();
+    tree::names(tree.stream(), &mut names);
+    let names = names.into_iter().map(TokenTree::from).collect();
+    let mut arguments: TokenStream = "@declare".parse().unwrap();
+    arguments.extend([
+        TokenTree::from(Group::new(Delimiter::Bracket, names)),
+        declaration,
+    ]);
+    let mut declare: TokenStream = "build_ui!".parse().unwrap();
+    declare.extend([TokenTree::from(Group::new(
+        Delimiter::Parenthesis,
+        arguments,
+    ))]);
+    declare
+}
+
+/// Declares the number of entities spawned by a `build_ui!` tree.
+///
+/// # Syntax
+/// `__count!(NAME (tree))` ⇒ `const NAME: usize = count;`, the leading
+/// `#[..]` attributes of `tree` are ignored.
+#[doc(hidden)]
+#[proc_macro]
+pub fn __count(input: TokenStream) -> TokenStream {
+    let mut input = input.into_iter();
+    let (Some(TokenTree::Ident(name)), Some(TokenTree::Group(tree))) = (input.next(), input.next())
+    else {
+        panic!("__count! expects `NAME (tree)`");
+    };
+    let mut tree: Vec<TokenTree> = tree.stream().into_iter().collect();
+    while matches!(&tree[..], [TokenTree::Punct(p), TokenTree::Group(_), ..] if p.as_char() == '#')
+    {
+        tree.drain(..2);
+    }
+    match tree::count(tree.into_iter().collect()) {
+        Ok(count) => {
+            let mut constant: TokenStream = "const".parse().unwrap();
+            constant.extend([TokenTree::from(name)]);
+            constant.extend(
+                format!(": usize = {count};")
+                    .parse::<TokenStream>()
+                    .unwrap(),
+            );
+            constant
+        }
+        Err((message, span)) => {
+            let message = format!("`#[count(..)]` requires a fixed number of entities, {message}");
+            compile_error(&message, span).collect()
+        }
+    }
+}
+
+/// Builds a `bevy::ui::Style` from a string of CSS declarations
+///
+/// ```rust,ignore
+/// css!("display: flex; justify-content: center; padding: 8px 16px")
+/// // Is strictly equivalent to
+/// style! { display: Display::Flex, justify_content: JustifyContent::Center, padding: rect!(16 px, 8 px) }
+/// ```
+///
+/// Supported properties are `display`, `position`, `flex-direction`,
+/// `flex-wrap`, `align-items`, `align-self`, `align-content`, `justify-content`,
+/// `justify-items`, `justify-self`, `width`, `height`, `min-width`,
+/// `min-height`, `max-width`, `max-height`, `flex-basis`, `left`, `right`, `top`,
+/// `bottom`, `row-gap`, `column-gap`, `gap`, `margin`, `padding`,
+/// `border-width`, `flex-grow`, `flex-shrink`, `flex` and `aspect-ratio`.
+///
+/// Lengths are `px`, `%`, `vw`, `vh`, `vmin`, `vmax`, `auto` or `0`.
+/// Like `style!`, the fields are those of bevy 0.11 and later (`width` rather
+/// than `size.width`).
+///
+/// Other properties and invalid values are compile errors.
+#[proc_macro]
+pub fn css(input: TokenStream) -> TokenStream {
+    let mut tokens: Vec<TokenTree> = input.into_iter().collect();
+    while let [TokenTree::Group(group)] = &tokens[..] {
+        if group.delimiter() != Delimiter::None {
+            break;
+        }
+        tokens = group.stream().into_iter().collect();
+    }
+    let (span, source) = match &tokens[..] {
+        [TokenTree::Literal(literal)] => (literal.span(), css::string_value(&literal.to_string())),
+        [first, ..] => (first.span(), None),
+        [] => (Span::call_site(), None),
+    };
+    let Some(source) = source else {
+        let error = compile_error("css! expects a string literal", span).collect();
+        return TokenTree::from(Group::new(Delimiter::Brace, error)).into();
+    };
+    let mut errors = TokenStream::new();
+    let mut fields: Vec<(&str, String)> = Vec::new();
+    for declaration in css::strip_comments(&source).split(';') {
+        let declaration = declaration.trim();
+        if declaration.is_empty() {
+            continue;
+        }
+        let Some((property, value)) = declaration.split_once(':') else {
+            let message = format!("expected `property: value`, got `{declaration}`");
+            errors.extend(compile_error(&message, span));
+            continue;
+        };
+        let property = property.trim();
+        match css::declaration(property, value.trim()) {
+            Ok(declarations) => {
+                for (field, value) in declarations {
+                    // Like in CSS, the last declaration wins
+                    fields.retain(|(set, _)| *set != field);
+                    fields.push((field, value));
+                }
+            }
+            Err(mut message) => {
+                if let Some(known) = suggestion(property, css::property_names()) {
+                    if known != property {
+                        message = format!("{message}, did you mean `{known}`?");
+                    }
+                }
+                errors.extend(compile_error(&message, span));
+            }
+        }
+    }
+    let fields: String = fields
+        .iter()
+        .map(|(field, value)| format!("{field}: {value}, "))
+        .collect();
+    let style = format!("bevy::ui::Style {{ {fields}..Default::default() }}");
+    let mut block = errors;
+    block.extend(style.parse::<TokenStream>().unwrap());
+    TokenTree::from(Group::new(Delimiter::Brace, block)).into()
+}
@@ -0,0 +1,219 @@
+//! Walking of `build_ui!` children lists, without a macro recursion per node.
+use proc_macro::{Delimiter, Group, Ident, Punct, Spacing, Span, TokenStream, TokenTree};
+
+/// Whether `token` is the `punct` punctuation.
+fn is_punct(token: Option<&TokenTree>, punct: char) -> bool {
+    matches!(token, Some(TokenTree::Punct(p)) if p.as_char() == punct)
+}
+
+/// Split a children list into its children.
+///
+/// Children are separated by `,`, `let pattern = value;` statements end at
+/// their `;`.
+pub(crate) fn children(list: TokenStream) -> Vec<Vec<TokenTree>> {
+    let mut children = Vec::new();
+    let mut child: Vec<TokenTree> = Vec::new();
+    for token in list {
+        let is_let =
+            matches!(child.first(), Some(TokenTree::Ident(ident)) if ident.to_string() == "let");
+        if is_punct(Some(&token), ',') && !is_let {
+            children.push(std::mem::take(&mut child));
+        } else if is_punct(Some(&token), ';') && is_let {
+            child.push(token);
+            children.push(std::mem::take(&mut child));
+        } else {
+            child.push(token);
+        }
+    }
+    children.push(child);
+    children.retain(|child| !child.is_empty());
+    children
+}
+
+/// The `as name` of the entities of a children list, in order.
+pub(crate) fn names(list: TokenStream, names: &mut Vec<Ident>) {
+    for child in children(list) {
+        child_names(&child, names);
+    }
+}
+
+/// The `as name` of the entities of a single child.
+fn child_names(child: &[TokenTree], names: &mut Vec<Ident>) {
+    let group = |token: &TokenTree, delimiter| match token {
+        TokenTree::Group(group) if group.delimiter() == delimiter => Some(group.stream()),
+        _ => None,
+    };
+    let keyword = match child.first() {
+        Some(TokenTree::Ident(ident)) => ident.to_string(),
+        _ => String::new(),
+    };
+    match keyword.as_str() {
+        // Children of the branches of an `if`, the conditions are parenthesized
+        "if" => {
+            for branch in child
+                .iter()
+                .filter_map(|token| group(token, Delimiter::Brace))
+            {
+                self::names(branch, names);
+            }
+        }
+        "for" => {
+            if let Some(body) = child
+                .last()
+                .and_then(|token| group(token, Delimiter::Brace))
+            {
+                self::names(body, names);
+            }
+        }
+        // Children of the `=> { .. }` arms
+        "match" => {
+            let arms = child
+                .last()
+                .and_then(|token| group(token, Delimiter::Brace));
+            let arms: Vec<TokenTree> = arms.into_iter().flatten().collect();
+            for arm in arms.windows(2) {
+                if let (true, Some(body)) = (
+                    is_punct(Some(&arm[0]), '>'),
+                    group(&arm[1], Delimiter::Brace),
+                ) {
+                    self::names(body, names);
+                }
+            }
+        }
+        // `repeat (count) preset..`
+        "repeat" => child_names(child.get(2..).unwrap_or_default(), names),
+        // The `as name` of those are not declared
+        "let" | "include" | "call" | "id" | "ids" | "batch" => {}
+        // `#[cfg(..)] preset..`
+        _ if is_punct(child.first(), '#') => child_names(child.get(2..).unwrap_or_default(), names),
+        // `template!(arguments)(slot1)(slot2)`, the slots are children lists
+        _ if is_punct(child.get(1), '!') || is_punct(child.get(1), ':') => {
+            let slots = child
+                .iter()
+                .skip_while(|token| group(token, Delimiter::Parenthesis).is_none());
+            for slot in slots
+                .skip(1)
+                .filter_map(|token| group(token, Delimiter::Parenthesis))
+            {
+                self::names(slot, names);
+            }
+        }
+        // `preset {styles} [components] (children) as name`, the preset can be
+        // an `(expression)`, `text` and `img` have no children
+        _ => {
+            let leaf = keyword == "text" || keyword == "img";
+            let mut tokens = child.iter().skip(1);
+            while let Some(token) = tokens.next() {
+                match token {
+                    TokenTree::Group(children)
+                        if !leaf && children.delimiter() == Delimiter::Parenthesis =>
+                    {
+                        self::names(children.stream(), names);
+                    }
+                    TokenTree::Ident(ident) if ident.to_string() == "as" => {
+                        if let Some(TokenTree::Ident(name)) = tokens.next() {
+                            names.push(name.clone());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// `build_ui!(@child opts (cmds) child);` for each child of `list`.
+pub(crate) fn siblings(cmds: Group, opts: TokenTree, list: Group) -> TokenStream {
+    let mut siblings = TokenStream::new();
+    for child in children(list.stream()) {
+        let mut arguments: TokenStream = "@child".parse().unwrap();
+        arguments.extend([opts.clone(), cmds.clone().into()]);
+        arguments.extend(child);
+        siblings.extend("build_ui!".parse::<TokenStream>().unwrap());
+        siblings.extend([
+            TokenTree::from(Group::new(Delimiter::Parenthesis, arguments)),
+            TokenTree::from(Punct::new(';', Spacing::Alone)),
+        ]);
+    }
+    siblings
+}
+
+/// The number of entities spawned by a children list, or why it isn't known
+/// at compile time.
+pub(crate) fn count(list: TokenStream) -> Result<usize, (String, Span)> {
+    children(list).iter().map(|child| child_count(child)).sum()
+}
+
+/// The `repeat (count)` or `batch (count)` integer literal.
+fn literal_count(
+    keyword: &str,
+    span: Span,
+    count: Option<&TokenTree>,
+) -> Result<usize, (String, Span)> {
+    let count = match count {
+        Some(TokenTree::Group(count)) => count.stream().into_iter().last(),
+        _ => None,
+    };
+    match count {
+        Some(TokenTree::Literal(literal)) => literal.to_string().replace('_', "").parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| {
+        (
+            format!("`{keyword}` requires an integer literal count"),
+            span,
+        )
+    })
+}
+
+/// The number of entities spawned by a single child.
+fn child_count(child: &[TokenTree]) -> Result<usize, (String, Span)> {
+    let (keyword, span) = match child.first() {
+        Some(TokenTree::Ident(ident)) => (ident.to_string(), ident.span()),
+        Some(token) => (String::new(), token.span()),
+        None => return Ok(0),
+    };
+    let unknown = |what: &str| Err((format!("{what} spawn an unknown number of entities"), span));
+    match keyword.as_str() {
+        "let" | "id" => Ok(0),
+        "if" | "match" => unknown("conditional children"),
+        "for" | "ids" => unknown("loops"),
+        "include" | "call" => unknown("fragments and functions"),
+        "repeat" => Ok(literal_count(&keyword, span, child.get(1))?
+            * child_count(child.get(2..).unwrap_or_default())?),
+        "batch" => literal_count(&keyword, span, child.get(1)),
+        _ if is_punct(child.first(), '#') => unknown("`#[cfg(..)]` children"),
+        _ if is_punct(child.get(1), '!') || is_punct(child.get(1), ':') => unknown("templates"),
+        // `preset {styles} [components] (children) as name`, the tooltips of
+        // the components are spawned as children. A `this` root isn't spawned,
+        // only its children are
+        _ => {
+            let leaf = keyword == "text" || keyword == "img";
+            let mut count = usize::from(keyword != "this");
+            for token in &child[1..] {
+                let TokenTree::Group(group) = token else {
+                    continue;
+                };
+                match group.delimiter() {
+                    Delimiter::Parenthesis if !leaf => count += self::count(group.stream())?,
+                    Delimiter::Bracket => {
+                        let components: Vec<TokenTree> = group.stream().into_iter().collect();
+                        for component in components.windows(2) {
+                            let [TokenTree::Ident(ident), TokenTree::Group(tooltip)] = component
+                            else {
+                                continue;
+                            };
+                            if ident.to_string() == "tooltip" {
+                                let tooltip: Vec<TokenTree> =
+                                    tooltip.stream().into_iter().collect();
+                                count += child_count(&tooltip)?;
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(count)
+        }
+    }
+}
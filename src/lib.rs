@@ -1,17 +1,245 @@
+#[doc(hidden)]
+pub use bevy_ui_build_macros_proc::__count;
+#[doc(hidden)]
+pub use bevy_ui_build_macros_proc::__field;
+#[doc(hidden)]
+pub use bevy_ui_build_macros_proc::__names;
+#[doc(hidden)]
+pub use bevy_ui_build_macros_proc::__siblings;
+#[doc(hidden)]
+pub use bevy_ui_build_macros_proc::__style;
+pub use bevy_ui_build_macros_proc::css;
+pub use patch::StylePatch;
+
+mod patch;
+
 /// Wrapper around the `bevy::ui::Val` enum
 ///
 /// # Syntax
 /// * `unit!(num1 px)` ⇒ `Val::Px(num1 as f32)`
 /// * `unit!(num1 pct)` ⇒ `Val::Percent(num1 as f32)`
+/// * `unit!(num1 vw)` ⇒ `Val::Vw(num1 as f32)`
+/// * `unit!(num1 vh)` ⇒ `Val::Vh(num1 as f32)`
+/// * `unit!(num1 vmin)` ⇒ `Val::VMin(num1 as f32)`
+/// * `unit!(num1 vmax)` ⇒ `Val::VMax(num1 as f32)`
+/// * `unit!(num1 fr)` ⇒ `GridTrack::fr(num1 as f32)`, see `track!` for other
+///   grid track sizes
 /// * `unit!(auto)` ⇒ `Val::Auto`
 /// * `unit!(undefined)` ⇒ `Val::Undefined`
+/// * `unit!(0)` or `unit!(zero)` ⇒ `Val::Px(0.0)`
+/// * `unit!(full)` ⇒ `Val::Percent(100.0)`
+///
+/// `num1` may be any expression, not only a literal: `unit!(width * 2.0 px)`
+/// ⇒ `Val::Px((width * 2.0) as f32)`.
 #[macro_export]
 macro_rules! unit {
-    (@with_value px $value:literal) => ( bevy::ui::Val::Px($value as f32));
-    (@with_value pct $value:literal) => ( bevy::ui::Val::Percent($value as f32));
+    (@with_value px $value:expr) => ( bevy::ui::Val::Px($value as f32));
+    (@with_value pct $value:expr) => ( bevy::ui::Val::Percent($value as f32));
+    (@with_value vw $value:expr) => ( bevy::ui::Val::Vw($value as f32));
+    (@with_value vh $value:expr) => ( bevy::ui::Val::Vh($value as f32));
+    (@with_value vmin $value:expr) => ( bevy::ui::Val::VMin($value as f32));
+    (@with_value vmax $value:expr) => ( bevy::ui::Val::VMax($value as f32));
+    (@with_value fr $value:expr) => ( bevy::ui::GridTrack::fr($value as f32));
+    (@with_value $val_unit:ident $value:expr) => (
+        compile_error!(concat!("unknown unit `", stringify!($val_unit), "`"))
+    );
+    (@with_value $val_unit:ident $($value:tt)*) => (
+        compile_error!(concat!("`", stringify!($($value)*), "` is not a valid expression"))
+    );
+    // Accumulate the tokens of the value expression until only the unit is left
+    (@value ($($value:tt)+) $val_unit:ident) => ( unit!(@with_value $val_unit $($value)+));
+    (@value ($($value:tt)*) $next:tt $($tail:tt)+) => ( unit!(@value ($($value)* $next) $($tail)+));
+    (@value () $value:tt) => (
+        compile_error!(concat!("expected a value followed by a unit, got `", stringify!($value), "`"))
+    );
     (auto) => ( bevy::ui::Val::Auto );
     (undefined) => ( bevy::ui::Val::Undefined );
-    ($value:literal $val_unit:ident) => ( unit!(@with_value $val_unit $value));
+    (0) => ( bevy::ui::Val::Px(0.0) );
+    (zero) => ( bevy::ui::Val::Px(0.0) );
+    (full) => ( bevy::ui::Val::Percent(100.0) );
+    ($($value:tt)+) => ( unit!(@value () $($value)+));
+}
+
+/// Several `unit!` at once, as an array of `bevy::ui::Val`
+///
+/// # Syntax
+/// * `units!(num1 val1, num2 val2, auto)` ⇒
+///   `[unit!(num1 val1), unit!(num2 val2), unit!(auto)]`
+///
+/// Useful to destructure several values at once:
+/// `let [gap, width] = units!(4 px, 30 pct);`
+#[macro_export]
+macro_rules! units {
+    // Split the comma-separated values
+    (@split ($($units:tt)*) ()) => ( [$($units)*] );
+    (@split ($($units:tt)*) ($($unit:tt)+)) => ( [$($units)* unit!($($unit)+)] );
+    (@split ($($units:tt)*) ($($unit:tt)+) , $($tail:tt)*) => (
+        units!(@split ($($units)* unit!($($unit)+),) () $($tail)*)
+    );
+    (@split ($($units:tt)*) ($($unit:tt)*) $next:tt $($tail:tt)*) => (
+        units!(@split ($($units)*) ($($unit)* $next) $($tail)*)
+    );
+    ($($units:tt)*) => ( units!(@split () () $($units)*) );
+}
+
+/// Wrapper around `bevy::ui::GridTrack` and `bevy::ui::RepeatedGridTrack`
+///
+/// # Syntax
+/// * `track!(num1 px)` ⇒ `GridTrack::px(num1 as f32)`
+/// * `track!(num1 pct)` ⇒ `GridTrack::percent(num1 as f32)`
+/// * `track!(num1 fr)` ⇒ `GridTrack::fr(num1 as f32)`
+/// * `track!(num1 flex)` ⇒ `GridTrack::flex(num1 as f32)`
+/// * `track!(auto)` ⇒ `GridTrack::auto()`, also `min_content` and `max_content`
+/// * `track!(fit_content num1 px)` ⇒ `GridTrack::fit_content_px(num1 as f32)`,
+///   also `pct`
+/// * `track!(minmax(num1 val1, num2 val2))` ⇒ `GridTrack::minmax(min, max)`
+///   where `min` and `max` are the track sizing functions for `num1 val1` and
+///   `num2 val2`. `num val` may also be `auto`, `min_content` or `max_content`
+/// * `track!(repeat(count, track1, track2))` ⇒
+///   `RepeatedGridTrack::repeat_many(count, vec![track!(track1), track!(track2)])`
+///
+/// The bevy constructors are generic over their return type, so `track!` can
+/// be used wherever a `GridTrack`, `RepeatedGridTrack` or `Vec` of those is
+/// expected.
+#[macro_export]
+macro_rules! track {
+    (@with_value px $value:expr) => ( bevy::ui::GridTrack::px($value as f32) );
+    (@with_value pct $value:expr) => ( bevy::ui::GridTrack::percent($value as f32) );
+    (@with_value fr $value:expr) => ( bevy::ui::GridTrack::fr($value as f32) );
+    (@with_value flex $value:expr) => ( bevy::ui::GridTrack::flex($value as f32) );
+    (@with_value $val_unit:ident $($value:tt)*) => (
+        compile_error!(concat!("unknown grid track unit `", stringify!($val_unit), "`"))
+    );
+    (@value ($($value:tt)+) $val_unit:ident) => ( track!(@with_value $val_unit $($value)+) );
+    (@value ($($value:tt)*) $next:tt $($tail:tt)+) => ( track!(@value ($($value)* $next) $($tail)+) );
+    (@fit_content px $value:expr) => ( bevy::ui::GridTrack::fit_content_px($value as f32) );
+    (@fit_content pct $value:expr) => ( bevy::ui::GridTrack::fit_content_percent($value as f32) );
+    (@min auto) => ( bevy::ui::MinTrackSizingFunction::Auto );
+    (@min min_content) => ( bevy::ui::MinTrackSizingFunction::MinContent );
+    (@min max_content) => ( bevy::ui::MinTrackSizingFunction::MaxContent );
+    (@min $value:tt px) => ( bevy::ui::MinTrackSizingFunction::Px($value as f32) );
+    (@min $value:tt pct) => ( bevy::ui::MinTrackSizingFunction::Percent($value as f32) );
+    (@max auto) => ( bevy::ui::MaxTrackSizingFunction::Auto );
+    (@max min_content) => ( bevy::ui::MaxTrackSizingFunction::MinContent );
+    (@max max_content) => ( bevy::ui::MaxTrackSizingFunction::MaxContent );
+    (@max $value:tt px) => ( bevy::ui::MaxTrackSizingFunction::Px($value as f32) );
+    (@max $value:tt pct) => ( bevy::ui::MaxTrackSizingFunction::Percent($value as f32) );
+    (@max $value:tt fr) => ( bevy::ui::MaxTrackSizingFunction::Fraction($value as f32) );
+    // Split comma-separated tracks into a `Vec`
+    (@vec ($($tracks:tt)*) ()) => ( vec![$($tracks)*] );
+    (@vec ($($tracks:tt)*) ($($track:tt)+)) => ( vec![$($tracks)* track!($($track)+)] );
+    (@vec ($($tracks:tt)*) ($($track:tt)+) , $($tail:tt)*) => (
+        track!(@vec ($($tracks)* track!($($track)+),) () $($tail)*)
+    );
+    (@vec ($($tracks:tt)*) ($($track:tt)*) $next:tt $($tail:tt)*) => (
+        track!(@vec ($($tracks)*) ($($track)* $next) $($tail)*)
+    );
+    (auto) => ( bevy::ui::GridTrack::auto() );
+    (min_content) => ( bevy::ui::GridTrack::min_content() );
+    (max_content) => ( bevy::ui::GridTrack::max_content() );
+    (fit_content $value:tt $val_unit:ident) => ( track!(@fit_content $val_unit $value) );
+    (minmax($min:tt $($min_unit:ident)?, $max:tt $($max_unit:ident)? $(,)?)) => (
+        bevy::ui::GridTrack::minmax(track!(@min $min $($min_unit)?), track!(@max $max $($max_unit)?))
+    );
+    (repeat($count:expr, $($tracks:tt)+)) => (
+        bevy::ui::RepeatedGridTrack::repeat_many($count, track!(@vec () () $($tracks)+))
+    );
+    ($($value:tt)+) => ( track!(@value () $($value)+) );
+}
+
+/// CSS `calc()`-like arithmetic on `bevy::ui::Val`
+///
+/// # Syntax
+/// * `calc!(num1 val1 + num2 val1 - num3 val1)` ⇒
+///   `unit!((num1 + num2 - num3) val1)`, all terms must have the same unit
+/// * `calc!(num1 pct - num2 px, of: length)` ⇒
+///   `Val::Px(num1 / 100.0 * length - num2)`, percentages are resolved against
+///   `length`, only `px` and `pct` may be mixed this way
+///
+/// Like with `unit!`, the numbers may be any expression: `calc!(-w px + 2 px)`.
+/// bevy has no `Val` resolved against the parent at layout time, so terms of
+/// different units can only be mixed with `, of: length`.
+#[macro_export]
+macro_rules! calc {
+    (@px ($of:expr) px $value:tt) => ( ($value as f32) );
+    (@px ($of:expr) pct $value:tt) => ( ($value as f32) / 100.0 * ($of as f32) );
+    (@px ($of:expr) $val_unit:ident $value:tt) => (
+        compile_error!(concat!("cannot resolve `", stringify!($val_unit), "` against a length"))
+    );
+    (@same $val_unit:ident ($($acc:tt)*)) => ( unit!(@with_value $val_unit $($acc)*) );
+    (@same px ($($acc:tt)*) ($op:tt) px $value:tt $($tail:tt)*) => (
+        calc!(@same px ($($acc)* $op ($value as f32)) $($tail)*)
+    );
+    (@same pct ($($acc:tt)*) ($op:tt) pct $value:tt $($tail:tt)*) => (
+        calc!(@same pct ($($acc)* $op ($value as f32)) $($tail)*)
+    );
+    (@same vw ($($acc:tt)*) ($op:tt) vw $value:tt $($tail:tt)*) => (
+        calc!(@same vw ($($acc)* $op ($value as f32)) $($tail)*)
+    );
+    (@same vh ($($acc:tt)*) ($op:tt) vh $value:tt $($tail:tt)*) => (
+        calc!(@same vh ($($acc)* $op ($value as f32)) $($tail)*)
+    );
+    (@same vmin ($($acc:tt)*) ($op:tt) vmin $value:tt $($tail:tt)*) => (
+        calc!(@same vmin ($($acc)* $op ($value as f32)) $($tail)*)
+    );
+    (@same vmax ($($acc:tt)*) ($op:tt) vmax $value:tt $($tail:tt)*) => (
+        calc!(@same vmax ($($acc)* $op ($value as f32)) $($tail)*)
+    );
+    (@same $val_unit:ident $acc:tt $op:tt $other_unit:ident $($tail:tt)*) => (
+        compile_error!(concat!(
+            "cannot mix `", stringify!($val_unit), "` and `", stringify!($other_unit),
+            "` without a reference length, add `, of: length` at the end of calc!"
+        ))
+    );
+    (@compute [(+) $val_unit:ident $value:tt $($terms:tt)*] ()) => (
+        calc!(@same $val_unit (($value as f32)) $($terms)*)
+    );
+    (@compute [$( ($op:tt) $val_unit:ident $value:tt )*] ($of:expr)) => (
+        bevy::ui::Val::Px(0.0 $( $op calc!(@px ($of) $val_unit $value) )*)
+    );
+    // `$maybe_unit` is followed by a `+` or a `-`, it ends the term if it is a unit
+    (@split px [$($terms:tt)*] $op:tt $value:tt $($tail:tt)+) => (
+        calc!(@terms [$($terms)* ($op) px $value] $($tail)+)
+    );
+    (@split pct [$($terms:tt)*] $op:tt $value:tt $($tail:tt)+) => (
+        calc!(@terms [$($terms)* ($op) pct $value] $($tail)+)
+    );
+    (@split vw [$($terms:tt)*] $op:tt $value:tt $($tail:tt)+) => (
+        calc!(@terms [$($terms)* ($op) vw $value] $($tail)+)
+    );
+    (@split vh [$($terms:tt)*] $op:tt $value:tt $($tail:tt)+) => (
+        calc!(@terms [$($terms)* ($op) vh $value] $($tail)+)
+    );
+    (@split vmin [$($terms:tt)*] $op:tt $value:tt $($tail:tt)+) => (
+        calc!(@terms [$($terms)* ($op) vmin $value] $($tail)+)
+    );
+    (@split vmax [$($terms:tt)*] $op:tt $value:tt $($tail:tt)+) => (
+        calc!(@terms [$($terms)* ($op) vmax $value] $($tail)+)
+    );
+    (@split $maybe_unit:ident $terms:tt $op:tt ($($value:tt)*) $next_op:tt () $($tail:tt)+) => (
+        calc!(@terms $terms $op ($($value)* $maybe_unit $next_op) $($tail)+)
+    );
+    // Accumulate the tokens of each term until its unit, looking for a
+    // trailing `, of: length`
+    (@terms [$($terms:tt)*] $op:tt ($($value:tt)+) $val_unit:ident) => (
+        calc!(@compute [$($terms)* ($op) $val_unit ($($value)+)] ())
+    );
+    (@terms [$($terms:tt)*] $op:tt ($($value:tt)+) $val_unit:ident , of: $of:expr) => (
+        calc!(@compute [$($terms)* ($op) $val_unit ($($value)+)] ($of))
+    );
+    (@terms $terms:tt $op:tt ($($value:tt)+) $maybe_unit:ident + $($tail:tt)+) => (
+        calc!(@split $maybe_unit $terms $op ($($value)+) + () $($tail)+)
+    );
+    (@terms $terms:tt $op:tt ($($value:tt)+) $maybe_unit:ident - $($tail:tt)+) => (
+        calc!(@split $maybe_unit $terms $op ($($value)+) - () $($tail)+)
+    );
+    (@terms $terms:tt $op:tt ($($value:tt)*) $next:tt $($tail:tt)*) => (
+        calc!(@terms $terms $op ($($value)* $next) $($tail)*)
+    );
+    (@terms $terms:tt $op:tt ($($value:tt)*)) => (
+        compile_error!(concat!("expected a value followed by a unit, got `", stringify!($($value)*), "`"))
+    );
+    ($($terms:tt)+) => ( calc!(@terms [] + () $($terms)+) );
 }
 
 /// Wrapper around `bevy::ui::Style`
@@ -28,25 +256,549 @@ macro_rules! unit {
 ///     ..Default.default()
 /// }
 /// ```
+///
+/// Use `..base` to build on top of an existing style rather than
+/// `Default::default()`:
+///
+/// ```rust,ignore
+/// const CARD: Style = style! { padding: 8 px, ..Style::DEFAULT };
+/// style! { ..CARD, margin: 4 px }
+/// // Is strictly equivalent to
+/// bevy::ui::Style { margin: rect!(4 px), ..CARD }
+/// ```
+///
+/// `Val` and `UiRect` fields accept the `unit!` syntax directly:
+///
+/// ```rust,ignore
+/// style! { margin: 5 px, flex_basis: 30 pct, width: auto }
+/// // Is strictly equivalent to
+/// style! { margin: rect!(5 px), flex_basis: unit!(30 pct), width: unit!(auto) }
+/// ```
+///
+/// The most common settings have a keyword:
+/// * `row` ⇒ `flex_direction: FlexDirection::Row`
+/// * `column` ⇒ `flex_direction: FlexDirection::Column`
+/// * `center` ⇒ `justify_content: JustifyContent::Center, align_items: AlignItems::Center`
+/// * `wrap` ⇒ `flex_wrap: FlexWrap::Wrap`
+/// * `absolute` ⇒ `position_type: PositionType::Absolute`
+///
+/// Like in CSS, `flex: grow shrink basis` sets `flex_grow`, `flex_shrink` and
+/// `flex_basis` at once, `basis` uses the `unit!` syntax: `flex: 1 0 auto`.
+///
+/// `gap: num1 val1` sets both `row_gap` and `column_gap` of bevy 0.11 and later,
+/// `gap: num1 val1 num2 val2` sets them to different values, like in CSS.
+///
+/// Like in CSS, `place_content: align justify` sets `align_content` and
+/// `justify_content`, `place_items: align justify` sets `align_items` and
+/// `justify_items`. With a single keyword, both fields get the same value:
+/// `place_content: center`. Keywords are the snake_case variant names, such as
+/// `start`, `flex_end` or `space_between`, of the enum of each field:
+/// `justify_items` has no `flex_start` and `flex_end`, the content fields have
+/// no `baseline`, and the items fields have no `space_*`. Other keywords are
+/// compile errors listing the valid ones.
+///
+/// `overflow: keyword` sets the `overflow` field, `keyword` is one of `visible`,
+/// `clip`, `clip_x` and `clip_y` on bevy 0.11 and later, and `hidden` on bevy
+/// 0.9 and 0.10.
+///
+/// `grid_columns: [track1, track2]` sets `grid_template_columns` to
+/// `vec![track!(track1), track!(track2)]`, `grid_rows` does the same for
+/// `grid_template_rows`: `grid_columns: [repeat(3, 1 fr), 200 px]`.
+///
+/// For the children of a grid, `grid(row: 2, col: 1 / 3)` sets `grid_row` and
+/// `grid_column` to `GridPlacement`s, with the CSS syntax: `2` starts at line
+/// 2, `1 / 3` spans from line 1 to 3, `span 2` spans 2 tracks, `2 / span 3`
+/// spans 3 tracks from line 2, and `auto` is auto-placed. Each axis is
+/// optional. Wrap negative lines in parenthesis: `(-1)`.
+///
+/// On bevy 0.11 and later, `Style` has separate `width` and `height` fields
+/// instead of `size`. Use `size!` as an entry, without field name, to set both:
+///
+/// ```rust,ignore
+/// style! { size!(100 pct, 40 px), flex_basis: unit!(10 pct) }
+/// // Is strictly equivalent to
+/// style! { width: unit!(100 pct), height: unit!(40 px), flex_basis: unit!(10 pct) }
+/// ```
+///
+/// `min_size!` and `max_size!` entries similarly set the `min_width`,
+/// `min_height`, `max_width` and `max_height` fields.
+///
+/// An `inset!` entry sets the `left`, `right`, `top` and `bottom` fields of
+/// bevy 0.11 and later, see `inset!`. `absolute(top: 0, right: 0)` is the same
+/// as `absolute, inset!(top: 0, right: 0)`.
+///
+/// Other macros used as entries set the field of the same name, for example
+/// `style! { margin!(auto, 10 px) }` is `style! { margin: margin!(auto, 10 px) }`.
+///
+/// `if condition { entries }` and `if condition { entries1 } else { entries2 }`
+/// entries conditionally set fields, they are applied after all other entries:
+///
+/// ```rust,ignore
+/// style! { width: 100 pct, if is_compact { padding: 2 px } }
+/// // Is equivalent to
+/// if is_compact {
+///     style! { width: 100 pct, padding: 2 px }
+/// } else {
+///     style! { width: 100 pct }
+/// }
+/// ```
+///
+/// Any other name without a value is a preset declared with `define_style!`,
+/// `style! { card, width: 50 pct }` is `style! { ..card(), width: 50 pct }`.
+///
+/// Field names are checked at compile time, misspelled fields are reported
+/// with a suggestion: ``"`Style` has no field `flex_dierction`, did you mean `flex_direction`?"``
 #[macro_export]
 macro_rules! style {
-    (@default ($default:expr) $($field:ident : $content:expr),*) => (
-        bevy::ui::Style { $($field : $content,)* .. $default }
+    (@fields ($default:expr) ($($fields:tt)*)) => (
+        $crate::__style!(($default) $($fields)*)
+    );
+    (@fields ($default:expr) ($($fields:tt)*) .. $base:expr $(, $($tail:tt)*)?) => (
+        style!(@fields ($base) ($($fields)*) $($($tail)*)?)
+    );
+    (@fields ($default:expr) ($($fields:tt)*) if $($tail:tt)*) => (
+        style!(@if ($default) ($($fields)*) () $($tail)*)
+    );
+    (@fields ($default:expr) ($($fields:tt)*) row $(, $($tail:tt)*)?) => (
+        style!(@fields ($default) ($($fields)* flex_direction: bevy::ui::FlexDirection::Row,) $($($tail)*)?)
+    );
+    (@fields ($default:expr) ($($fields:tt)*) column $(, $($tail:tt)*)?) => (
+        style!(@fields ($default) ($($fields)* flex_direction: bevy::ui::FlexDirection::Column,) $($($tail)*)?)
+    );
+    (@fields ($default:expr) ($($fields:tt)*) center $(, $($tail:tt)*)?) => (
+        style!(@fields ($default) (
+            $($fields)*
+            justify_content: bevy::ui::JustifyContent::Center,
+            align_items: bevy::ui::AlignItems::Center,
+        ) $($($tail)*)?)
+    );
+    (@fields ($default:expr) ($($fields:tt)*) wrap $(, $($tail:tt)*)?) => (
+        style!(@fields ($default) ($($fields)* flex_wrap: bevy::ui::FlexWrap::Wrap,) $($($tail)*)?)
+    );
+    (@fields ($default:expr) ($($fields:tt)*)
+        flex: $grow:tt $shrink:tt $basis:tt $($basis_unit:ident)? $(, $($tail:tt)*)?
+    ) => (
+        style!(@fields ($default) (
+            $($fields)*
+            flex_grow: $grow as f32,
+            flex_shrink: $shrink as f32,
+            flex_basis: unit!($basis $($basis_unit)?),
+        ) $($($tail)*)?)
+    );
+    (@fields ($default:expr) ($($fields:tt)*) gap: $gap:tt $gap_unit:ident $(, $($tail:tt)*)?) => (
+        style!(@fields ($default) (
+            $($fields)*
+            row_gap: unit!($gap $gap_unit),
+            column_gap: unit!($gap $gap_unit),
+        ) $($($tail)*)?)
+    );
+    (@fields ($default:expr) ($($fields:tt)*)
+        gap: $row:tt $row_unit:ident $column:tt $column_unit:ident $(, $($tail:tt)*)?
+    ) => (
+        style!(@fields ($default) (
+            $($fields)*
+            row_gap: unit!($row $row_unit),
+            column_gap: unit!($column $column_unit),
+        ) $($($tail)*)?)
+    );
+    (@fields ($default:expr) ($($fields:tt)*) place_content: $align:ident $($justify:ident)? $(, $($tail:tt)*)?) => (
+        style!(@fields ($default) (
+            $($fields)*
+            align_content: style!(@align AlignContent $align),
+            justify_content: style!(@align JustifyContent $($justify)? $align),
+        ) $($($tail)*)?)
+    );
+    (@fields ($default:expr) ($($fields:tt)*) place_items: $align:ident $($justify:ident)? $(, $($tail:tt)*)?) => (
+        style!(@fields ($default) (
+            $($fields)*
+            align_items: style!(@align AlignItems $align),
+            justify_items: style!(@align JustifyItems $($justify)? $align),
+        ) $($($tail)*)?)
+    );
+    (@fields ($default:expr) ($($fields:tt)*) overflow: $overflow:ident $(, $($tail:tt)*)?) => (
+        style!(@fields ($default) ($($fields)* overflow: style!(@overflow $overflow),) $($($tail)*)?)
+    );
+    (@fields ($default:expr) ($($fields:tt)*) grid_columns: [$($tracks:tt)*] $(, $($tail:tt)*)?) => (
+        style!(@fields ($default) ($($fields)* grid_template_columns: track!(@vec () () $($tracks)*),) $($($tail)*)?)
+    );
+    (@fields ($default:expr) ($($fields:tt)*) grid_rows: [$($tracks:tt)*] $(, $($tail:tt)*)?) => (
+        style!(@fields ($default) ($($fields)* grid_template_rows: track!(@vec () () $($tracks)*),) $($($tail)*)?)
+    );
+    (@fields ($default:expr) ($($fields:tt)*) size!($($size:tt)*) $(, $($tail:tt)*)?) => (
+        style!(@fields ($default) (
+            $($fields)*
+            width: size!(@width $($size)*),
+            height: size!(@height $($size)*),
+        ) $($($tail)*)?)
+    );
+    (@fields ($default:expr) ($($fields:tt)*) min_size!($($size:tt)*) $(, $($tail:tt)*)?) => (
+        style!(@fields ($default) (
+            $($fields)*
+            min_width: size!(@width $($size)*),
+            min_height: size!(@height $($size)*),
+        ) $($($tail)*)?)
+    );
+    (@fields ($default:expr) ($($fields:tt)*) max_size!($($size:tt)*) $(, $($tail:tt)*)?) => (
+        style!(@fields ($default) (
+            $($fields)*
+            max_width: size!(@width $($size)*),
+            max_height: size!(@height $($size)*),
+        ) $($($tail)*)?)
+    );
+    (@fields ($default:expr) ($($fields:tt)*) absolute $(, $($tail:tt)*)?) => (
+        style!(@fields ($default) ($($fields)* position_type: bevy::ui::PositionType::Absolute,) $($($tail)*)?)
+    );
+    (@fields ($default:expr) ($($fields:tt)*) absolute($($inset:tt)+) $(, $($tail:tt)*)?) => (
+        inset!(@style ($default)
+            ($($fields)* position_type: bevy::ui::PositionType::Absolute,)
+            ($($($tail)*)?) [] () $($inset)+
+        )
+    );
+    (@fields ($default:expr) ($($fields:tt)*) grid($($grid:tt)+) $(, $($tail:tt)*)?) => (
+        style!(@grid ($default) ($($fields)*) ($($($tail)*)?) $($grid)+)
+    );
+    (@fields ($default:expr) ($($fields:tt)*) inset!($($inset:tt)*) $(, $($tail:tt)*)?) => (
+        inset!(@style ($default) ($($fields)*) ($($($tail)*)?) [] () $($inset)*)
+    );
+    (@fields ($default:expr) ($($fields:tt)*) $preset:ident $(, $($tail:tt)*)?) => (
+        style!(@fields ($preset()) ($($fields)*) $($($tail)*)?)
+    );
+    (@fields ($default:expr) ($($fields:tt)*) $field:ident ! $args:tt $(, $($tail:tt)*)?) => (
+        style!(@fields ($default) ($($fields)* $field: $field! $args,) $($($tail)*)?)
+    );
+    (@fields ($default:expr) ($($fields:tt)*) $field:ident : $content:expr $(, $($tail:tt)*)?) => (
+        style!(@fields ($default) ($($fields)* $field: $content,) $($($tail)*)?)
+    );
+    // Not an expression, such as `10 px`, collect the tokens up to the next comma
+    (@fields ($default:expr) ($($fields:tt)*) $field:ident : $($tail:tt)*) => (
+        style!(@value ($default) ($($fields)*) $field () $($tail)*)
+    );
+    (@value $default:tt ($($fields:tt)*) $field:ident ($($value:tt)*) $(, $($tail:tt)*)?) => (
+        style!(@fields $default ($($fields)* $field: $($value)*,) $($($tail)*)?)
+    );
+    (@value $default:tt $fields:tt $field:ident ($($value:tt)*) $next:tt $($tail:tt)*) => (
+        style!(@value $default $fields $field ($($value)* $next) $($tail)*)
+    );
+    // Collect the condition tokens up to the `{ entries }` block
+    (@if $default:tt ($($fields:tt)*) ($($condition:tt)+)
+        {$($then:tt)*} else {$($otherwise:tt)*} $(, $($tail:tt)*)?
+    ) => (
+        style!(@fields $default (
+            $($fields)* if ($($condition)+) {$($then)*} else {$($otherwise)*},
+        ) $($($tail)*)?)
+    );
+    (@if $default:tt ($($fields:tt)*) ($($condition:tt)+) {$($then:tt)*} $(, $($tail:tt)*)?) => (
+        style!(@fields $default ($($fields)* if ($($condition)+) {$($then)*} else {},) $($($tail)*)?)
+    );
+    (@if $default:tt $fields:tt ($($condition:tt)*) $next:tt $($tail:tt)*) => (
+        style!(@if $default $fields ($($condition)* $next) $($tail)*)
+    );
+    // With two keywords, the second one is the fallback of `place_*: align`
+    (@align $enum:ident $keyword:ident $_align:ident) => ( style!(@align $enum $keyword) );
+    (@align $enum:ident start) => ( bevy::ui::$enum::Start );
+    (@align $enum:ident end) => ( bevy::ui::$enum::End );
+    (@align $enum:ident center) => ( bevy::ui::$enum::Center );
+    (@align AlignItems flex_start) => ( bevy::ui::AlignItems::FlexStart );
+    (@align AlignItems flex_end) => ( bevy::ui::AlignItems::FlexEnd );
+    (@align AlignItems baseline) => ( bevy::ui::AlignItems::Baseline );
+    (@align AlignItems stretch) => ( bevy::ui::AlignItems::Stretch );
+    (@align JustifyItems baseline) => ( bevy::ui::JustifyItems::Baseline );
+    (@align JustifyItems stretch) => ( bevy::ui::JustifyItems::Stretch );
+    (@align AlignContent flex_start) => ( bevy::ui::AlignContent::FlexStart );
+    (@align AlignContent flex_end) => ( bevy::ui::AlignContent::FlexEnd );
+    (@align AlignContent stretch) => ( bevy::ui::AlignContent::Stretch );
+    (@align AlignContent space_between) => ( bevy::ui::AlignContent::SpaceBetween );
+    (@align AlignContent space_around) => ( bevy::ui::AlignContent::SpaceAround );
+    (@align AlignContent space_evenly) => ( bevy::ui::AlignContent::SpaceEvenly );
+    (@align JustifyContent flex_start) => ( bevy::ui::JustifyContent::FlexStart );
+    (@align JustifyContent flex_end) => ( bevy::ui::JustifyContent::FlexEnd );
+    (@align JustifyContent stretch) => ( bevy::ui::JustifyContent::Stretch );
+    (@align JustifyContent space_between) => ( bevy::ui::JustifyContent::SpaceBetween );
+    (@align JustifyContent space_around) => ( bevy::ui::JustifyContent::SpaceAround );
+    (@align JustifyContent space_evenly) => ( bevy::ui::JustifyContent::SpaceEvenly );
+    (@align AlignItems $keyword:ident) => ( compile_error!(concat!(
+        "unknown `align_items` alignment `", stringify!($keyword), "`, expected one of ",
+        "`start`, `end`, `flex_start`, `flex_end`, `center`, `baseline`, `stretch`"
+    )) );
+    (@align JustifyItems $keyword:ident) => ( compile_error!(concat!(
+        "unknown `justify_items` alignment `", stringify!($keyword), "`, expected one of ",
+        "`start`, `end`, `center`, `baseline`, `stretch`"
+    )) );
+    (@align AlignContent $keyword:ident) => ( compile_error!(concat!(
+        "unknown `align_content` alignment `", stringify!($keyword), "`, expected one of ",
+        "`start`, `end`, `flex_start`, `flex_end`, `center`, `stretch`, `space_between`, ",
+        "`space_around`, `space_evenly`"
+    )) );
+    (@align JustifyContent $keyword:ident) => ( compile_error!(concat!(
+        "unknown `justify_content` alignment `", stringify!($keyword), "`, expected one of ",
+        "`start`, `end`, `flex_start`, `flex_end`, `center`, `stretch`, `space_between`, ",
+        "`space_around`, `space_evenly`"
+    )) );
+    (@overflow visible) => ( bevy::ui::Overflow::visible() );
+    (@overflow clip) => ( bevy::ui::Overflow::clip() );
+    (@overflow clip_x) => ( bevy::ui::Overflow::clip_x() );
+    (@overflow clip_y) => ( bevy::ui::Overflow::clip_y() );
+    (@overflow hidden) => ( bevy::ui::Overflow::Hidden );
+    (@overflow scroll) => ( compile_error!("scrolling requires bevy 0.15, where `Style` is replaced by `Node`") );
+    (@overflow scroll_x) => ( compile_error!("scrolling requires bevy 0.15, where `Style` is replaced by `Node`") );
+    (@overflow scroll_y) => ( compile_error!("scrolling requires bevy 0.15, where `Style` is replaced by `Node`") );
+    (@overflow $overflow:ident) => ( $overflow );
+    // Split the comma-separated `grid(row: .., col: ..)`
+    (@grid $default:tt $fields:tt ($($tail:tt)*) $(,)?) => (
+        style!(@fields $default $fields $($tail)*)
     );
-    ($($field:ident : $content:expr,)*) => (
-        style!(@default (Default::default()) $($field : $content),*)
+    (@grid $default:tt $fields:tt $tail:tt row: $($grid:tt)+) => (
+        style!(@placement $default $fields $tail grid_row () $($grid)+)
     );
+    (@grid $default:tt $fields:tt $tail:tt col: $($grid:tt)+) => (
+        style!(@placement $default $fields $tail grid_column () $($grid)+)
+    );
+    (@grid $default:tt $fields:tt $tail:tt $axis:ident $($grid:tt)*) => (
+        compile_error!(concat!("unknown grid axis `", stringify!($axis), "`, expected `row` or `col`"))
+    );
+    (@placement $default:tt ($($fields:tt)*) $tail:tt $field:ident ($($value:tt)+) $(, $($grid:tt)*)?) => (
+        style!(@grid $default ($($fields)* $field: style!(@placement $($value)+),) $tail $($($grid)*)?)
+    );
+    (@placement $default:tt $fields:tt $tail:tt $field:ident ($($value:tt)*) $next:tt $($grid:tt)*) => (
+        style!(@placement $default $fields $tail $field ($($value)* $next) $($grid)*)
+    );
+    (@placement auto) => ( bevy::ui::GridPlacement::auto() );
+    (@placement span $span:tt) => ( bevy::ui::GridPlacement::span($span) );
+    (@placement $start:tt / span $span:tt) => ( bevy::ui::GridPlacement::start_span($start, $span) );
+    (@placement $start:tt / $end:tt) => ( bevy::ui::GridPlacement::start_end($start, $end) );
+    (@placement $start:tt) => ( bevy::ui::GridPlacement::start($start) );
+    (@default ($default:expr) $($entries:tt)*) => (
+        style!(@fields ($default) () $($entries)*)
+    );
+    ($($entries:tt)*) => (
+        style!(@default (Default::default()) $($entries)*)
+    );
+}
+
+/// Declare named `bevy::ui::Style` presets
+///
+/// Each preset becomes a function returning its `style!`:
+///
+/// ```rust,ignore
+/// define_style! {
+///     pub card: { column, padding: 8 px },
+///     toolbar: { row, ..card() },
+/// }
+/// // Is strictly equivalent to
+/// pub fn card() -> bevy::ui::Style { style! { column, padding: 8 px } }
+/// fn toolbar() -> bevy::ui::Style { style! { row, ..card() } }
+/// ```
+///
+/// Presets can then be referenced by name in `style!` and in `build_ui!` style
+/// blocks: `style! { card, margin: 4 px }`.
+#[macro_export]
+macro_rules! define_style {
+    ($( $(#[$attr:meta])* $vis:vis $name:ident : {$($entries:tt)*} ),* $(,)?) => {
+        $(
+            $(#[$attr])*
+            $vis fn $name() -> bevy::ui::Style {
+                style! { $($entries)* }
+            }
+        )*
+    };
+}
+
+/// A patch changing only the given fields of an existing `bevy::ui::Style`
+///
+/// Accepts the same entries as `style!`, except presets and `..base`, and
+/// returns a `StylePatch`, applied with `StylePatch::apply`, the other fields
+/// are left untouched:
+///
+/// ```rust,ignore
+/// let highlight = style_patch! { border: 2 px, padding: 6 px };
+/// for mut style in &mut buttons {
+///     highlight.apply(&mut style);
+/// }
+/// // Is strictly equivalent to
+/// for mut style in &mut buttons {
+///     style.border = rect!(2 px);
+///     style.padding = rect!(6 px);
+/// }
+/// ```
+///
+/// The patch can be stored in a component to apply it later, its patched
+/// fields are listed by `StylePatch::fields`, and `StylePatch::merge` adds the
+/// fields of another patch. `if` entries add their fields only when their
+/// condition holds.
+#[macro_export]
+macro_rules! style_patch {
+    ($($entries:tt)*) => (
+        style!(@fields (__patch) (__patch: $crate,) $($entries)*)
+    );
+}
+
+/// Same as `rect!`, for the `margin` field of `bevy::ui::Style`
+///
+/// `margin!(auto)` centers the node in its parent, `margin!(auto, num1 val1)`
+/// centers it horizontally.
+///
+/// Can be used as an entry of `style!`: `style! { margin!(auto) }`
+#[macro_export]
+macro_rules! margin {
+    ($($rect:tt)*) => ( rect!($($rect)*) );
+}
+
+/// Same as `rect!`, for the `padding` field of `bevy::ui::Style`
+///
+/// Can be used as an entry of `style!`: `style! { padding!(4 px) }`
+#[macro_export]
+macro_rules! padding {
+    ($($rect:tt)*) => ( rect!($($rect)*) );
+}
+
+/// Same as `rect!`, for the `border` field of `bevy::ui::Style`
+///
+/// Can be used as an entry of `style!`: `style! { border!(1 px) }`
+#[macro_export]
+macro_rules! border {
+    ($($rect:tt)*) => ( rect!($($rect)*) );
+}
+
+/// Same as `rect!`, for the `position` field of `bevy::ui::Style`
+///
+/// Used as an entry of `style!`, sets the `left`, `right`, `top` and `bottom`
+/// fields of bevy 0.11 and later. With named sides, only the named sides are
+/// set:
+///
+/// ```rust,ignore
+/// style! { position_type: PositionType::Absolute, inset!(top: 10 px, x: 0) }
+/// // Is strictly equivalent to
+/// style! {
+///     position_type: PositionType::Absolute,
+///     top: unit!(10 px),
+///     left: unit!(0),
+///     right: unit!(0),
+/// }
+/// ```
+#[macro_export]
+macro_rules! inset {
+    (@fields $default:tt ($($fields:tt)*) ($($tail:tt)*) []) => (
+        style!(@fields $default ($($fields)*) $($tail)*)
+    );
+    (@fields $default:tt ($($fields:tt)*) $tail:tt [(x: $($value:tt)+) $($args:tt)*]) => (
+        inset!(@fields $default ($($fields)* left: unit!($($value)+), right: unit!($($value)+),) $tail [$($args)*])
+    );
+    (@fields $default:tt ($($fields:tt)*) $tail:tt [(y: $($value:tt)+) $($args:tt)*]) => (
+        inset!(@fields $default ($($fields)* top: unit!($($value)+), bottom: unit!($($value)+),) $tail [$($args)*])
+    );
+    (@fields $default:tt ($($fields:tt)*) $tail:tt [(left: $($value:tt)+) $($args:tt)*]) => (
+        inset!(@fields $default ($($fields)* left: unit!($($value)+),) $tail [$($args)*])
+    );
+    (@fields $default:tt ($($fields:tt)*) $tail:tt [(right: $($value:tt)+) $($args:tt)*]) => (
+        inset!(@fields $default ($($fields)* right: unit!($($value)+),) $tail [$($args)*])
+    );
+    (@fields $default:tt ($($fields:tt)*) $tail:tt [(top: $($value:tt)+) $($args:tt)*]) => (
+        inset!(@fields $default ($($fields)* top: unit!($($value)+),) $tail [$($args)*])
+    );
+    (@fields $default:tt ($($fields:tt)*) $tail:tt [(bottom: $($value:tt)+) $($args:tt)*]) => (
+        inset!(@fields $default ($($fields)* bottom: unit!($($value)+),) $tail [$($args)*])
+    );
+    (@fields $default:tt $fields:tt $tail:tt [(.. $($value:tt)*) $($args:tt)*]) => (
+        compile_error!("an `inset!` style entry only sets the named sides, `..` is not supported")
+    );
+    (@fields $default:tt $fields:tt $tail:tt [($side:ident : $($value:tt)*) $($args:tt)*]) => (
+        compile_error!(concat!("`", stringify!($side), "` is not a side or an axis"))
+    );
+    (@positional $default:tt ($($fields:tt)*) ($($tail:tt)*) [$(($($arg:tt)*))*]) => (
+        style!(@fields $default (
+            $($fields)*
+            left: rect!($($($arg)*),*).left,
+            right: rect!($($($arg)*),*).right,
+            top: rect!($($($arg)*),*).top,
+            bottom: rect!($($($arg)*),*).bottom,
+        ) $($tail)*)
+    );
+    (@named $default:tt $fields:tt $tail:tt [($side:ident : $($value:tt)+) $($args:tt)*]) => (
+        inset!(@fields $default $fields $tail [($side : $($value)+) $($args)*])
+    );
+    (@named $default:tt $fields:tt $tail:tt [$($args:tt)*]) => (
+        inset!(@positional $default $fields $tail [$($args)*])
+    );
+    // Split the comma-separated arguments of an `inset!` style entry
+    (@style $default:tt $fields:tt $tail:tt [$($args:tt)*] ()) => (
+        inset!(@named $default $fields $tail [$($args)*])
+    );
+    (@style $default:tt $fields:tt $tail:tt [$($args:tt)*] ($($arg:tt)+)) => (
+        inset!(@named $default $fields $tail [$($args)* ($($arg)+)])
+    );
+    (@style $default:tt $fields:tt $tail:tt [$($args:tt)*] ($($arg:tt)+) , $($rest:tt)*) => (
+        inset!(@style $default $fields $tail [$($args)* ($($arg)+)] () $($rest)*)
+    );
+    (@style $default:tt $fields:tt $tail:tt [$($args:tt)*] ($($arg:tt)*) $next:tt $($rest:tt)*) => (
+        inset!(@style $default $fields $tail [$($args)*] ($($arg)* $next) $($rest)*)
+    );
+    ($($rect:tt)*) => ( rect!($($rect)*) );
 }
 
 /// Wrapper around `bevy::ui::Size::new`
 ///
 /// # Syntax
 /// * `size!(num1 val1, num2 val2)` ⇒ `Size::new(unit!(num1 val1), unit!(num2 val2))`
+/// * `size!(width: num1 val1, height: num2 val2)` ⇒ same as above, `width` and
+///   `height` may be given in any order
+/// * `size!(full)` ⇒ `size!(100 pct, 100 pct)`, `full` can also be used for
+///   a single axis: `size!(full, 40 px)`
+/// * `size!(val1 vec)` ⇒ `size!(vec.x val1, vec.y val1)`, where `vec` is an
+///   expression evaluating to a `Vec2`
+///
+/// Like `unit!` and `rect!`, it can be used in const contexts:
+/// `const FULL: Size = size!(100 pct, 100 pct);`
+///
+/// `size!` can also be used as an entry of `style!` to set the `width` and
+/// `height` fields of bevy 0.11 and later.
 #[macro_export]
 macro_rules! size {
+    (@width $x:tt $($x_unit:ident)?, $y:tt $($y_unit:ident)? $(,)?) => ( unit!($x $($x_unit)?) );
+    (@height $x:tt $($x_unit:ident)?, $y:tt $($y_unit:ident)? $(,)?) => ( unit!($y $($y_unit)?) );
+    (@$axis:ident full) => ( size!(@$axis full, full) );
+    (@$axis:ident width: $x:tt $($x_unit:ident)?, height: $y:tt $($y_unit:ident)? $(,)?) => (
+        size!(@$axis $x $($x_unit)?, $y $($y_unit)?)
+    );
+    (@$axis:ident height: $y:tt $($y_unit:ident)?, width: $x:tt $($x_unit:ident)? $(,)?) => (
+        size!(@$axis $x $($x_unit)?, $y $($y_unit)?)
+    );
+    (@width $val_unit:ident $vec:expr) => ( unit!(@with_value $val_unit $vec.x) );
+    (@height $val_unit:ident $vec:expr) => ( unit!(@with_value $val_unit $vec.y) );
+    (full) => ( size!(full, full) );
+    (width: $x:tt $($x_unit:ident)?, height: $y:tt $($y_unit:ident)? $(,)?) => (
+        size!($x $($x_unit)?, $y $($y_unit)?)
+    );
+    (height: $y:tt $($y_unit:ident)?, width: $x:tt $($x_unit:ident)? $(,)?) => (
+        size!($x $($x_unit)?, $y $($y_unit)?)
+    );
     ($x:tt $($x_unit:ident)?, $y:tt $($y_unit:ident)?) => (
-        bevy::ui::Size::new(unit!($x $($x_unit)?), unit!($y $($y_unit)?))
+        bevy::ui::Size { width: unit!($x $($x_unit)?), height: unit!($y $($y_unit)?) }
     );
+    ($val_unit:ident $vec:expr) => ({
+        let vec = $vec;
+        bevy::ui::Size {
+            width: unit!(@with_value $val_unit vec.x),
+            height: unit!(@with_value $val_unit vec.y),
+        }
+    });
+}
+
+/// Same as `size!`, for the `min_size` field of `bevy::ui::Style`
+///
+/// Used as an entry of `style!`, sets the `min_width` and `min_height` fields
+/// of bevy 0.11 and later.
+#[macro_export]
+macro_rules! min_size {
+    ($($size:tt)*) => ( size!($($size)*) );
+}
+
+/// Same as `size!`, for the `max_size` field of `bevy::ui::Style`
+///
+/// Used as an entry of `style!`, sets the `max_width` and `max_height` fields
+/// of bevy 0.11 and later.
+#[macro_export]
+macro_rules! max_size {
+    ($($size:tt)*) => ( size!($($size)*) );
 }
 
 /// Define a `bevy::ui::UiRect` similarly to how you would define it in CSS.
@@ -64,6 +816,14 @@ macro_rules! size {
 ///     bottom: unit!(num2 val2),
 /// }
 ///
+/// // three arguments, like CSS
+/// rect!(num1 val1, num2 val2, num3 val3) == Rect {
+///     top: unit!(num1 val1),
+///     left: unit!(num2 val2),
+///     right: unit!(num2 val2),
+///     bottom: unit!(num3 val3),
+/// }
+///
 /// // four arguments
 /// rect!(num1 val1, num2 val2, num3 val3, num4 val4) == Rect {
 ///     left: unit!(num1 val1),
@@ -71,31 +831,119 @@ macro_rules! size {
 ///     right: unit!(num3 val3),
 ///     bottom: unit!(num4 val4),
 /// }
+///
+/// // named sides, in any order, the other sides are `Val::Auto`
+/// rect!(left: num1 val1, bottom: num2 val2) == Rect {
+///     left: unit!(num1 val1),
+///     top: Val::Auto,
+///     right: Val::Auto,
+///     bottom: unit!(num2 val2),
+/// }
+///
+/// // named axes, `x` sets left and right, `y` sets top and bottom, can be
+/// // mixed with named sides
+/// rect!(x: num1 val1, y: num2 val2) == Rect {
+///     left: unit!(num1 val1),
+///     right: unit!(num1 val1),
+///     top: unit!(num2 val2),
+///     bottom: unit!(num2 val2),
+/// }
+///
+/// // single axis, the other sides are `Val::Auto`
+/// rect!(horizontal num1 val1) == rect!(x: num1 val1)
+/// rect!(vertical num1 val1) == rect!(y: num1 val1)
+///
+/// // named sides with a default for the other sides
+/// rect!(left: num1 val1, ..num2 val2) == Rect {
+///     left: unit!(num1 val1),
+///     top: unit!(num2 val2),
+///     right: unit!(num2 val2),
+///     bottom: unit!(num2 val2),
+/// }
+///
+/// // const contexts
+/// const MARGIN: UiRect = rect!(4 px);
+/// const INDENT: UiRect = rect!(left: 16 px);
 /// ```
+///
+/// Like in `unit!`, the `num` values may be arbitrary expressions:
+/// `rect!(gutter px, GUTTER * 2.0 px)`.
 #[macro_export]
 macro_rules! rect {
-    ($x:tt $($x_unit:ident)?) => (
-        bevy::ui::UiRect::all(unit!($x $($x_unit)?))
-    );
-    (
-        $left:tt $($left_unit:ident)?, $top:tt $($top_unit:ident)?,
-        $right:tt $($right_unit:ident)?, $bottom:tt $($bottom_unit:ident)? $(,)?
-    ) => (
+    (@side () $default:tt) => ( $default );
+    (@side ($($value:tt)+) $default:tt) => ( unit!($($value)+) );
+    (@named $left:tt $top:tt $right:tt $bottom:tt $default:tt []) => (
         bevy::ui::UiRect {
-            left: unit!($left $($left_unit)?),
-            top: unit!($top $($top_unit)?),
-            right: unit!($right $($right_unit)?),
-            bottom: unit!($bottom $($bottom_unit)?),
+            left: rect!(@side $left $default),
+            top: rect!(@side $top $default),
+            right: rect!(@side $right $default),
+            bottom: rect!(@side $bottom $default),
         }
     );
-    ($x:tt $($x_unit:ident)?, $y:tt $($y_unit:ident)?) => (
-        bevy::ui::UiRect {
-            left: unit!($x $($x_unit)?),
-            top: unit!($y $($y_unit)?),
-            right: unit!($x $($x_unit)?),
-            bottom: unit!($y $($y_unit)?),
-        }
+    (@named $left:tt $top:tt $right:tt $bottom:tt $default:tt [(.. $($value:tt)+)]) => (
+        rect!(@named $left $top $right $bottom (unit!($($value)+)) [])
+    );
+    (@named () $top:tt $right:tt $bottom:tt $default:tt [(left: $($value:tt)+) $($args:tt)*]) => (
+        rect!(@named ($($value)+) $top $right $bottom $default [$($args)*])
+    );
+    (@named $left:tt () $right:tt $bottom:tt $default:tt [(top: $($value:tt)+) $($args:tt)*]) => (
+        rect!(@named $left ($($value)+) $right $bottom $default [$($args)*])
+    );
+    (@named $left:tt $top:tt () $bottom:tt $default:tt [(right: $($value:tt)+) $($args:tt)*]) => (
+        rect!(@named $left $top ($($value)+) $bottom $default [$($args)*])
+    );
+    (@named $left:tt $top:tt $right:tt () $default:tt [(bottom: $($value:tt)+) $($args:tt)*]) => (
+        rect!(@named $left $top $right ($($value)+) $default [$($args)*])
+    );
+    (@named () $top:tt () $bottom:tt $default:tt [(x: $($value:tt)+) $($args:tt)*]) => (
+        rect!(@named ($($value)+) $top ($($value)+) $bottom $default [$($args)*])
+    );
+    (@named $left:tt () $right:tt () $default:tt [(y: $($value:tt)+) $($args:tt)*]) => (
+        rect!(@named $left ($($value)+) $right ($($value)+) $default [$($args)*])
+    );
+    (@named $left:tt $top:tt $right:tt $bottom:tt $default:tt [($side:ident : $($value:tt)*) $($args:tt)*]) => (
+        compile_error!(concat!(
+            "`", stringify!($side), "` is not a side or an axis, or it is specified twice"
+        ))
+    );
+    (@named $left:tt $top:tt $right:tt $bottom:tt $default:tt [($($arg:tt)*) $($args:tt)*]) => (
+        compile_error!(concat!(
+            "expected a named side, got `", stringify!($($arg)*),
+            "`, named sides and positional values cannot be mixed"
+        ))
+    );
+    (@args [($side:ident : $($value:tt)+) $($args:tt)*]) => (
+        rect!(@named () () () () (bevy::ui::Val::Auto) [($side : $($value)+) $($args)*])
+    );
+    (@args [(horizontal $($value:tt)+)]) => ( rect!(@args [(x: $($value)+)]) );
+    (@args [(vertical $($value:tt)+)]) => ( rect!(@args [(y: $($value)+)]) );
+    (@args [$x:tt]) => ({
+        let value = unit!$x;
+        bevy::ui::UiRect { left: value, top: value, right: value, bottom: value }
+    });
+    (@args [$x:tt $y:tt]) => ({
+        let x = unit!$x;
+        let y = unit!$y;
+        bevy::ui::UiRect { left: x, top: y, right: x, bottom: y }
+    });
+    (@args [$top:tt $x:tt $bottom:tt]) => ({
+        let x = unit!$x;
+        bevy::ui::UiRect { left: x, top: unit!$top, right: x, bottom: unit!$bottom }
+    });
+    (@args [$left:tt $top:tt $right:tt $bottom:tt]) => (
+        bevy::ui::UiRect { left: unit!$left, top: unit!$top, right: unit!$right, bottom: unit!$bottom }
     );
+    (@args [$($args:tt)*]) => ( compile_error!("rect! expects 1, 2, 3 or 4 values") );
+    // Split the comma-separated arguments
+    (@split [$($args:tt)*] ()) => ( rect!(@args [$($args)*]) );
+    (@split [$($args:tt)*] ($($arg:tt)+)) => ( rect!(@args [$($args)* ($($arg)+)]) );
+    (@split [$($args:tt)*] ($($arg:tt)+) , $($tail:tt)*) => (
+        rect!(@split [$($args)* ($($arg)+)] () $($tail)*)
+    );
+    (@split [$($args:tt)*] ($($arg:tt)*) $next:tt $($tail:tt)*) => (
+        rect!(@split [$($args)*] ($($arg)* $next) $($tail)*)
+    );
+    ($($args:tt)*) => ( rect!(@split [] () $($args)*) );
 }
 
 /// Define a bevy UI and spawns it using `cmd`
@@ -110,13 +958,14 @@ macro_rules! rect {
 ///     #[cmd(commands)]
 ///     // The "preset" is an identifier, see doc
 ///     $entity
-///         // Style modifiers. Supposing $entity is a `NodeBundle`, does:
+///         // Style modifiers. Supposing $entity is a `NodeBundle`, a
+///         // `ButtonBundle` or any bundle with a `style` field, does:
 ///         // $entity.style = style!{ flex_whatever: Whatever }
 ///         // Leads to a compilation error if $entity doesn't have a `style`
 ///         // field
 ///         { flex_whatever: Whatever }
 ///         // Additional components and bundles. Translates to
-///         // $entity.insert_bundle(bundle1).insert_bundle(bundle2).insert(comp1).insert(comp2)
+///         // $entity.insert((bundle1, bundle2, comp1, comp2))
 ///         // If you don't care for bundles or comp, just leave the left or
 ///         // right of the ; blank
 ///         [bundle1, bundl2 ;comp1, comp2]
@@ -128,12 +977,230 @@ macro_rules! rect {
 /// }
 /// ```
 ///
+/// Children lists may also contain:
+/// * `if (predicate) { children }`, optionally followed by `else { children }`
+///   or `else if (predicate) { children }` chains: spawn `children` only if
+///   `predicate` is true
+/// * `for pattern in (iterator) { children }`: spawn `children` once per item
+///   of `iterator`, `pattern` can be used in the style, components and
+///   children of `children`
+/// * `match (value) { pattern => { children }, .. }`: spawn the `children` of
+///   the first arm matching `value`, arms may have `if` guards
+/// * `let pattern = value;`: a binding usable by the following children, note
+///   that it ends with `;` rather than `,`
+/// * `include fragment`: spawn the children of a `ui_fragment!`, see its
+///   documentation
+/// * `call(function, arguments..)`: call `function(cmds, arguments..)`, where
+///   `cmds` is the `&mut ChildBuilder` of the parent, to spawn children with a
+///   hand-written function
+/// * `template!(arguments)(slot children)..`: call a template declared with
+///   `define_template!`, see its documentation
+/// * `#[cfg(predicate)] child`: spawn `child` only if the `cfg` predicate
+///   holds, for example debug-only widgets with `#[cfg(debug_assertions)]`
+/// * `repeat(count) child`: spawn `child` `count` times, `repeat(i: count)`
+///   binds the index of each copy, from `0` to `count - 1`, to `i`, it can
+///   be used in the style, components and children of `child`
+/// * `batch(count) preset{..}[..;..]`: spawn `count` copies of `preset` with
+///   `World::insert_or_spawn_batch`, much faster than listing `preset` `count`
+///   times. The preset and components are cloned for each copy, the copies
+///   can't have children, `as name` or observers, and they are not returned
+///   by `#[collect]`. Requires bevy 0.10 or later.
+///
+/// Any entity of the tree may be followed by `as name`, this declares `name`
+/// as a `mut` local variable of type `Entity`, holding the id of the spawned
+/// entity, usable after the `build_ui!`. Entities not spawned, such as in an
+/// `if` with a false predicate, have the id `Entity::from_raw(u32::MAX)`,
+/// entities spawned in a `for` loop have the id of the last one.
+///
+/// With `#[out(Struct)]` after `#[cmd(..)]`, the names are not declared as
+/// variables, instead `build_ui!` returns a `Struct { name1, name2 }`.
+/// `Struct` must have one `Entity` field per name:
+///
+/// ```rust,ignore
+/// #[derive(Resource)]
+/// struct MenuEntities { play: Entity, quit: Entity }
+///
+/// let entities = build_ui! {
+///     #[cmd(commands)]
+///     #[out(MenuEntities)]
+///     vertical(button as play, button as quit)
+/// };
+/// commands.insert_resource(entities);
+/// ```
+///
+/// With `#[collect]` after `#[cmd(..)]`, `build_ui!` returns a `Vec<Entity>`
+/// of all the entities it spawned, parents before their children:
+/// `let spawned = build_ui! { #[cmd(commands)] #[collect] vertical(square) };`
+///
+/// With `#[count(NAME)]` after `#[cmd(..)]`, `build_ui!` also declares a
+/// `const NAME: usize` with the number of entities the tree spawns, to
+/// preallocate or to check an entity budget in tests:
+/// `build_ui! { #[cmd(commands)] #[count(MENU_SIZE)] vertical(square, square) };`
+/// declares `MENU_SIZE = 3`. A `this` root isn't counted, only its children.
+/// Like `as name`, it requires the `build_ui!` to be a statement. The number of
+/// entities must be known at compile time: `if`, `for`, `match`, `ids(..)`,
+/// `#[cfg(..)]`, `include`, `call(..)` and template children are compile
+/// errors, `repeat(..)` and `batch(..)` require an integer literal count.
+///
+/// The top level may have several comma-separated roots, such as a HUD and a
+/// pause overlay, and the same `if`, `for`, `match` and `let` as children lists.
+/// `build_ui!` then returns `()` rather than the `EntityCommands` of the root.
+///
+/// With `#[parent(entity)]` after `#[cmd(..)]`, the roots are spawned as
+/// children of the already existing `entity` rather than at the top level.
+///
+/// With `#[state(GameState::Menu)]` after `#[cmd(..)]`, the whole tree is
+/// despawned when exiting `GameState::Menu`. This adds `state: GameState::Menu`
+/// to the style modifiers of the root, see below, so it requires a single root
+/// accepting style modifiers.
+/// Likewise, `#[camera(entity)]` adds `camera: entity` to render the tree with
+/// the `entity` camera.
+///
+/// With the `names` crate feature, every spawned entity gets a `Name`
+/// component, its `as name` if any, or its preset otherwise, such as
+/// `Name::new("vertical")`, to tell the entities apart in inspectors and logs.
+/// `(expression)` presets are named `"preset"`.
+///
+/// With the `trace` crate feature, the spawning of each entity and its
+/// children is wrapped in an `info_span!`, named like the `Name` of the
+/// `names` feature, so that complex screens show up with meaningful names in
+/// tracing tools such as Tracy. With `#[cmd(..)]`, the spans only measure the
+/// queuing of the commands, use `#[world(..)]` to measure the spawning itself.
+///
+/// Replace `#[cmd(commands)]` by `#[world(world)]` to spawn the tree directly
+/// in a `&mut World`, for example in exclusive systems and tests. The other
+/// attributes still follow it. `id(..)`, `ids(..)` and `batch(..)` children
+/// require `Commands` and are not supported with `#[world(..)]`.
+///
+/// Bundles and components are inserted as a single tuple bundle, so a node
+/// can have up to 15 of them. They are cloned before being inserted, prefix a
+/// component with `move` to insert it by value instead, for components that
+/// are not `Clone`: `[; move sender]`. A moved component can't be used in a
+/// `for` loop, since it can only be inserted once.
+///
+/// With `#[move]` after `#[cmd(..)]`, nothing is cloned: the presets, bundles
+/// and components are all inserted by value. Each of them can then only be
+/// used once, in a `for` loop, declare them in the loop with `let`:
+///
+/// ```rust,ignore
+/// build_ui! {
+///     #[cmd(commands)]
+///     #[move]
+///     vertical(
+///         for name in (names) {
+///             let label = TextBundle::from_section(name, style.clone());
+///             label
+///         }
+///     )
+/// }
+/// ```
+///
+/// The style modifiers work with any preset bundle with a `style` field, such
+/// as `NodeBundle`, `ButtonBundle` or `ImageBundle`. Like all presets, the
+/// bundle is cloned, so a bundle that isn't `Clone`, such as `ImageBundle` in
+/// bevy 0.14, needs `#[move]`. With a `style: {..}` entry,
+/// the style modifiers are in the `style: {..}` entry and the other entries set
+/// other fields of the preset bundle:
+/// `icon{style: {width: 32 px}, image: handles.icon.clone().into()}`.
+///
+/// The style modifiers may also contain `background: color` and
+/// `border_color: color`, inserted as `BackgroundColor` and `BorderColor`
+/// components rather than set in the `Style`:
+/// `button{padding: 8 px, background: Color::NAVY}`. Likewise, `radius: 8 px`
+/// inserts a `BorderRadius` (bevy 0.14). `z: 10` inserts a `ZIndex::Local(10)`,
+/// ordering the node among its siblings, and `z: global 100` a
+/// `ZIndex::Global(100)`, ordering it among all the nodes, for overlays.
+/// `visibility: Hidden` inserts `Visibility::Hidden`, to declare in place
+/// panels that start hidden (bevy 0.11).
+/// `role: Button` and `label: "Start game"` insert an `AccessibilityNode` with
+/// the given AccessKit `Role` and name, for screen readers (bevy 0.10).
+/// Without `role`, the role is `Role::Unknown`.
+/// `state: GameState::Menu` inserts a `StateScoped(GameState::Menu)`, so that
+/// the node and its children are despawned when exiting the state (bevy 0.14).
+/// `camera: entity` inserts a `TargetCamera(entity)`, so that a root node and
+/// its children are rendered by the given camera, for split-screen and
+/// render-to-texture UIs (bevy 0.13 to 0.15).
+///
+/// An `if predicate => component` entry inserts `component` only if
+/// `predicate` is true: `[; Focus, if selected => Highlighted]`. An `option?`
+/// entry, where `option` is an `Option` variable or field, or any parenthesized
+/// `Option` expression, inserts its content only if it is `Some`:
+/// `[; item.badge?, (icon_of(item))?]`.
+///
+/// The components may contain `on_click: |trigger, mut commands| { .. }`
+/// entries, adding the closure as an observer of `Pointer<Click>` on the entity.
+/// Likewise, `on_over`, `on_out` and `on_drag` observe `Pointer<Over>`,
+/// `Pointer<Out>` and `Pointer<Drag>`, for hover effects and drag interactions.
+/// The observer parameters following `trigger` are `Commands` unless they have
+/// an explicit type: `|trigger, query: Query<&mut Text>|`. Any other observer
+/// is added with an `observe(observer)` entry: `[; observe(on_hover)]`.
+/// A `tooltip(preset)` entry spawns the `preset` bundle as a hidden child,
+/// shown while the pointer is over the entity, with `on_over` and `on_out`
+/// observers: `button[; tooltip(hint)]`. The tooltip is a preset, such as
+/// `let hint = (Text::new("Pause"), Node::default());` on bevy 0.15, it can't
+/// declare `as name`, nor be a `text` or `img` node, their bundles don't exist
+/// in bevy 0.15.
+/// Observers require bevy 0.14, the pointer events require bevy 0.15 for
+/// `bevy_picking`:
+///
+/// ```rust,ignore
+/// build_ui! {
+///     #[cmd(commands)]
+///     button[; on_click: |_, mut commands| commands.insert_resource(Paused)](
+///         text("Pause")
+///     )
+/// }
+/// ```
+///
 /// The `$entity` in the macro may be one of the following:
-/// * `id(Entity)`: inserts a pre-existing entity as child of containing entity
+/// * `id(Entity)`: inserts a pre-existing entity as child of containing entity.
+///   It accepts style modifiers, applied as a `style_patch!` to its `Style`,
+///   plain components and children, added with commands:
+///   `id(e){width: 30 pct}[;Focus](text("Saved"))`. The entity keeps its
+///   place among its siblings, reserved by an empty entity that is despawned
+///   once the siblings are spawned.
+/// * `ids(entities)`: inserts all the pre-existing entities of `entities`, any
+///   `IntoIterator<Item = Entity>`, in order, as children of the containing
+///   entity, for content spawned elsewhere: `ids(scene_roots.iter().copied())`.
+///   Like `id(..)`, they keep their place among their siblings.
 /// * `$ident`: where `$ident` is the name of a local variable of type
 ///   `T: ComponentBundle`. Spawn the bundle as base to insert extra components
 ///   to. Useful to not repeat yourself.
 /// * `entity`: spawn an empty bundle as base to insert extra components to.
+/// * `(expression)`: any parenthesized expression, such as a function call, a
+///   struct literal or a field access, used like a `$ident` preset:
+///   `(make_card(&item)){width: 20 pct}(text(item.name.clone()))`. It is
+///   evaluated where it is spawned, so once per item in a `for` loop, and it
+///   is moved rather than cloned. With a closure, this is a factory preset,
+///   for bundles that are not `Clone` or shouldn't be shared:
+///   `let card = || NodeBundle { .. };` then `(card())`.
+/// * `this`: as the root of the tree only, rather than spawning a new entity,
+///   add the components and children to the entity of the `EntityCommands`
+///   given in `#[cmd(..)]`: `build_ui! { #[cmd(commands.entity(e))] this[;Menu](button) }`.
+///   It doesn't accept style modifiers.
+/// * `text("Hello")`: spawn a `TextBundle` with the given text and the default
+///   text style. Like other entities, it accepts style modifiers and
+///   components before the text: `text{margin: 4 px}[;Label]("Hello")`.
+///   The `font`, `size` and `color` of the `TextStyle` may follow the text:
+///   `text("Score", font: handles.bold, size: 24, color: Color::WHITE)`.
+///   Text with several sections is a list of spans, each span is a value
+///   optionally followed by a `TextStyle` variable: `text(["HP: " bold, hp red])`.
+/// * `button`: like any `$ident`, but the `Button`, `Interaction` and
+///   `FocusPolicy::Block` components are also inserted, so that the node is
+///   clickable even when the `button` preset is a plain `NodeBundle`. They
+///   are only inserted if the preset has no `Button` component, a
+///   `ButtonBundle` preset keeps its own `Interaction` and `FocusPolicy`. Also
+///   applies to `batch(..) button`.
+/// * `img("ui/icon.png")`: spawn an `ImageBundle` loading the image with the
+///   `AssetServer` given with the `#[assets(asset_server)]` attribute after
+///   `#[cmd(..)]`. Without `#[assets(..)]`, the argument is a `Handle<Image>`:
+///   `img(handles.icon.clone())`. Accepts style modifiers and components like
+///   `text`.
+/// * `overlay`: spawn a full-screen, absolutely positioned `NodeBundle` with a
+///   `ZIndex::Global(100)`, above the rest of the UI, as the root of dialogs
+///   and pause screens. Set its backdrop with the `background` style modifier
+///   and override its z-index with `z: global ..`:
+///   `overlay{background: Color::rgba(0.0, 0.0, 0.0, 0.5), center}(dialog)`.
 ///
 /// # Example
 ///
@@ -207,112 +1274,836 @@ macro_rules! rect {
 /// ```
 #[macro_export]
 macro_rules! build_ui {
-    (@preset entity) => (());
-    (@preset $anything_else:ident) => ($anything_else);
-    (@preset $node:ident {$($styles:tt)*}) => (
-        bevy::ui::node_bundles::NodeBundle {
-            style: style!(@default ($node.style.clone()) $($styles)*),
-            .. $node.clone()
-        }
+    (@preset $opts:tt entity) => (());
+    (@preset $opts:tt $anything_else:ident) => (build_ui!(@preset_value $anything_else $opts $anything_else));
+    (@preset $opts:tt $node:ident {$($styles:tt)*}) => (
+        build_ui!(@fields $opts $node () [] () $($styles)*)
+    );
+    // Split the comma-separated style modifiers, with a `style: {..}` entry,
+    // the other entries are fields of the bundle rather than of its `Style`
+    (@fields $opts:tt $node:ident () [$( ($( $styles:tt )*) )*] ()) => (
+        build_ui!(@fields $opts $node (bundle) [(style : {$($( $styles )*,)*})] ())
+    );
+    (@fields $opts:tt $node:ident (bundle) [$( $fields:tt )*] ()) => ({
+        let mut bundle = build_ui!(@preset_value $node $opts $node);
+        $( build_ui!(@field bundle $node $fields); )*
+        bundle
+    });
+    (@fields $opts:tt $node:ident $mode:tt $fields:tt ($( $entry:tt )+)) => (
+        build_ui!(@fields $opts $node $mode $fields ($( $entry )+) ,)
+    );
+    (@fields $opts:tt $node:ident $mode:tt $fields:tt () , $( $tail:tt )*) => (
+        build_ui!(@fields $opts $node $mode $fields () $( $tail )*)
+    );
+    (@fields $opts:tt $node:ident $mode:tt [$( $fields:tt )*] (style : {$( $style:tt )*}) , $( $tail:tt )*) => (
+        build_ui!(@fields $opts $node (bundle) [$( $fields )* (style : {$( $style )*})] () $( $tail )*)
+    );
+    (@fields $opts:tt $node:ident $mode:tt [$( $fields:tt )*] ($( $entry:tt )+) , $( $tail:tt )*) => (
+        build_ui!(@fields $opts $node $mode [$( $fields )* ($( $entry )+)] () $( $tail )*)
     );
-    // if-else terminal
-    (@child_list list: (if ($predicate:expr) { $( $if_true:tt )* } else { $( $if_false:tt )* } $(,)?),
-        cmds: $cmds:expr, prefix: ($( $prefix:tt )*),
+    (@fields $opts:tt $node:ident $mode:tt $fields:tt ($( $entry:tt )*) $next:tt $( $tail:tt )*) => (
+        build_ui!(@fields $opts $node $mode $fields ($( $entry )* $next) $( $tail )*)
+    );
+    // A preset without `style` field is reported at the preset
+    (@field $bundle:ident $node:ident (style : {$( $style:tt )*})) => (
+        $crate::__field!($bundle $node style) = style!(@default
+            (std::mem::take(&mut $crate::__field!($bundle $node style)))
+            $( $style )*
+        )
+    );
+    (@field $bundle:ident $node:ident ($field:ident : $value:expr)) => ( $bundle.$field = $value );
+    (@field $bundle:ident $node:ident ($( $entry:tt )*)) => (
+        compile_error!(concat!("expected a `field: value` bundle field, found `", stringify!($( $entry )*), "`"))
+    );
+    // `(expression)` presets are evaluated at each use, so they are moved
+    // rather than cloned
+    (@preset_value __build_ui_preset $opts:tt $value:expr) => ( $value );
+    (@preset_value $preset:ident $opts:tt $value:expr) => ( build_ui!(@clone $opts $value) );
+    (@clone {collect: $collect:tt, assets: $assets:tt, clone: (clone), world: $world:tt} $value:expr) => ( $value.clone() );
+    (@clone {collect: $collect:tt, assets: $assets:tt, clone: (), world: $world:tt} $value:expr) => ( $value );
+    // Children lists are split by `__siblings!` into a `@child` statement per
+    // child, so that large lists don't recurse once per sibling
+    (@child_list list: ($( $list:tt )*), cmds: $cmds:expr, opts: $opts:tt $(,)?) => (
+        $crate::__siblings!(($cmds) $opts ($( $list )*))
+    );
+    // if, else if and else
+    (@child $opts:tt ($cmds:expr)
+        if ($predicate:expr) { $( $if_true:tt )* }
+        $( else if ($predicates:expr) { $( $if_trues:tt )* } )*
+        $( else { $( $if_false:tt )* } )?
     ) => (
-        $( $prefix )*
         if $predicate {
-            build_ui!(@child_list list: ($( $if_true )*), cmds: $cmds, prefix: (),);
-        } else {
-            build_ui!(@child_list list: ($( $if_false )*), cmds: $cmds, prefix: (),);
+            build_ui!(@child_list list: ($( $if_true )*), cmds: $cmds, opts: $opts);
+        } $( else if $predicates {
+            build_ui!(@child_list list: ($( $if_trues )*), cmds: $cmds, opts: $opts);
+        } )* $( else {
+            build_ui!(@child_list list: ($( $if_false )*), cmds: $cmds, opts: $opts);
+        } )?
+    );
+    (@child $opts:tt ($cmds:expr) for $pattern:pat in ($iter:expr) { $( $body:tt )* }) => (
+        for $pattern in $iter {
+            build_ui!(@child_list list: ($( $body )*), cmds: $cmds, opts: $opts);
         }
     );
-    // if terminal
-    (@child_list list: (if ($predicate:expr) { $( $if_true:tt )* } $(,)?),
-        cmds: $cmds:expr, prefix: ($( $prefix:tt )*),
+    (@child $opts:tt ($cmds:expr)
+        match ($scrutinee:expr) {
+            $( $pattern:pat $(if $guard:expr)? => { $( $arm:tt )* } $(,)? )*
+        }
     ) => (
-        $( $prefix )*
-        if $predicate {
-            build_ui!(@child_list list: ($( $if_true )*), cmds: $cmds, prefix: (),);
+        match $scrutinee {
+            $( $pattern $(if $guard)? => {
+                build_ui!(@child_list list: ($( $arm )*), cmds: $cmds, opts: $opts);
+            } )*
         }
     );
-    // if-else with tail
-    (@child_list list: (
-            if ($predicate:expr) { $( $if_true:tt )* } else { $( $if_false:tt )* }
-            , $( $tail:tt )+
-        ),
-        cmds: $cmds:expr, prefix: ($( $prefix:tt )*),
+    // `(expression)` preset, bound to a variable to be used like any preset
+    (@child $opts:tt ($cmds:expr) ( $preset:expr ) $( $rest:tt )*) => (
+        let __build_ui_preset = $preset;
+        build_ui!(@child $opts ($cmds) __build_ui_preset $( $rest )*)
+    );
+    // let binding, in scope for the following children
+    (@child $opts:tt ($cmds:expr) let $pattern:pat = $value:expr;) => ( let $pattern = $value; );
+    // include fragment, a `ui_fragment!` declared elsewhere
+    (@child $opts:tt ($cmds:expr) include $fragment:ident) => ( $fragment!($cmds, $opts) );
+    // call(function, arguments..), a function spawning children itself
+    (@child $opts:tt ($cmds:expr) call ( $function:expr $(, $arguments:expr)* $(,)? )) => (
+        $function($cmds $(, $arguments)*)
+    );
+    // slot!(name) in a `define_template!`
+    (@child $opts:tt ($cmds:expr) slot ! ( $slot:ident )) => ( $slot($cmds) );
+    // template!(..)(..), a `define_template!` call
+    (@child $opts:tt ($cmds:expr)
+        $template:ident $(:: $path:ident)* ! ( $( $arguments:expr ),* $(,)? ) $( ( $( $slot:tt )* ) )*
     ) => (
-        build_ui! ( @child_list
-            list: ( $( $tail )+ ),
-            cmds: $cmds,
-            prefix: ($( $prefix )*
-                if $predicate {
-                    build_ui!(@child_list list: ($( $if_true )*), cmds: $cmds, prefix: (),);
-                } else {
-                    build_ui!(@child_list list: ($( $if_false )*), cmds: $cmds, prefix: (),);
-                }
-            ),
+        $template $(:: $path)* ($cmds, $( $arguments, )* $( |cmds| {
+            build_ui!(@child_list list: ($( $slot )*), cmds: cmds, opts: $opts);
+        }, )*)
+    );
+    // #[cfg(..)] child, only spawned if the configuration predicate holds
+    (@child $opts:tt ($cmds:expr)
+        #[cfg($( $cfg:tt )*)]
+        $preset:ident $( { $($syl:tt)* } )? $( [ $($bc:tt)* ] )? $( ( $( $c:tt )* ) )? $(as $name:ident)?
+    ) => (
+        #[cfg($( $cfg )*)]
+        {
+            build_ui!(@child $opts ($cmds)
+                $preset $( { $($syl)* } )? $( [ $($bc)* ] )? $( ( $($c)* ) )? $(as $name)?
+            );
+        }
+    );
+    // repeat, a `for` loop over `0..count`
+    (@child $opts:tt ($cmds:expr)
+        repeat ($index:ident : $count:expr)
+        $preset:ident $( { $($syl:tt)* } )? $( [ $($bc:tt)* ] )? $( ( $( $c:tt )* ) )? $(as $name:ident)?
+    ) => (
+        for $index in 0..$count {
+            build_ui!(@child $opts ($cmds)
+                $preset $( { $($syl)* } )? $( [ $($bc)* ] )? $( ( $($c)* ) )? $(as $name)?
+            );
+        }
+    );
+    (@child $opts:tt ($cmds:expr)
+        repeat ($count:expr)
+        $preset:ident $( { $($syl:tt)* } )? $( [ $($bc:tt)* ] )? $( ( $( $c:tt )* ) )? $(as $name:ident)?
+    ) => (
+        for _ in 0..$count {
+            build_ui!(@child $opts ($cmds)
+                $preset $( { $($syl)* } )? $( [ $($bc)* ] )? $( ( $($c)* ) )? $(as $name)?
+            );
+        }
+    );
+    (@child $opts:tt ($cmds:expr) batch ($count:expr) $preset:ident $( { $($syl:tt)* } )? $( [ $($bc:tt)* ] )?) => (
+        build_ui!{ @batch $opts #[cmd($cmds)] ($count) $preset $( { $($syl)* } )? $( [ $($bc)* ] )? }
+    );
+    (@child $opts:tt ($cmds:expr)
+        id ( $id:expr ) $( { $($syl:tt)* } )? $( [ $($bc:tt)* ] )? $( ( $( $c:tt )* ) )?
+    ) => (
+        build_ui!{ @node $opts #[cmd($cmds)] id ($id) $( { $($syl)* } )? $( [ $($bc)* ] )? $( ( $($c)* ) )? }
+    );
+    (@child $opts:tt ($cmds:expr)
+        $preset:ident $( { $($syl:tt)* } )? $( [ $($bc:tt)* ] )? $( ( $( $c:tt )* ) )? $(as $name:ident)?
+    ) => (
+        build_ui!{ @node $opts #[cmd($cmds)] $preset $( { $($syl)* } )? $( [ $($bc)* ] )? $( ( $($c)* ) )? $(as $name)? }
+    );
+    (@declare [$( $names:ident )*] ((out $out:path) $assets:tt $clone:tt $world:tt $parent:tt $( $tree:tt )*)) => ({
+        $( let mut $names = bevy::ecs::entity::Entity::from_raw(u32::MAX); )*
+        build_ui!(@root $parent {collect: (), assets: $assets, clone: $clone, world: $world} $( $tree )*);
+        $out { $( $names ),* }
+    });
+    (@declare [$( $names:ident )*] ((collect) $assets:tt $clone:tt $world:tt $parent:tt $( $tree:tt )*)) => ({
+        let mut spawned = Vec::new();
+        $( let mut $names = bevy::ecs::entity::Entity::from_raw(u32::MAX); )*
+        build_ui!(@root $parent {collect: (spawned), assets: $assets, clone: $clone, world: $world} $( $tree )*);
+        spawned
+    });
+    (@declare [] (() $assets:tt $clone:tt $world:tt $parent:tt $( $tree:tt )*)) => (
+        build_ui!(@root $parent {collect: (), assets: $assets, clone: $clone, world: $world} $( $tree )*)
+    );
+    (@declare [$( $names:ident )+] (() $assets:tt $clone:tt $world:tt $parent:tt $( $tree:tt )*)) => (
+        $( let mut $names = bevy::ecs::entity::Entity::from_raw(u32::MAX); )+
+        build_ui!(@root $parent {collect: (), assets: $assets, clone: $clone, world: $world} $( $tree )*)
+    );
+    // Attributes following `#[cmd(..)]`
+    (@attrs $mode:tt $assets:tt $clone:tt $parent:tt $cmds:tt #[out($out:path)] $( $tree:tt )+) => (
+        build_ui!(@attrs (out $out) $assets $clone $parent $cmds $( $tree )+)
+    );
+    (@attrs $mode:tt $assets:tt $clone:tt $parent:tt $cmds:tt #[collect] $( $tree:tt )+) => (
+        build_ui!(@attrs (collect) $assets $clone $parent $cmds $( $tree )+)
+    );
+    (@attrs $mode:tt $assets:tt $clone:tt $parent:tt $cmds:tt #[count($count:ident)] $( $tree:tt )+) => (
+        $crate::__count!($count ($( $tree )+));
+        build_ui!(@attrs $mode $assets $clone $parent $cmds $( $tree )+)
+    );
+    (@attrs $mode:tt $_assets:tt $clone:tt $parent:tt $cmds:tt #[assets($assets:expr)] $( $tree:tt )+) => (
+        build_ui!(@attrs $mode ($assets) $clone $parent $cmds $( $tree )+)
+    );
+    (@attrs $mode:tt $assets:tt $clone:tt $parent:tt $cmds:tt #[move] $( $tree:tt )+) => (
+        build_ui!(@attrs $mode $assets () $parent $cmds $( $tree )+)
+    );
+    (@attrs $mode:tt $assets:tt $clone:tt $_parent:tt $cmds:tt #[parent($parent:expr)] $( $tree:tt )+) => (
+        build_ui!(@attrs $mode $assets $clone ($parent) $cmds $( $tree )+)
+    );
+    (@attrs $mode:tt $assets:tt $clone:tt $parent:tt $cmds:tt #[state($state:expr)] $( $tree:tt )+) => (
+        build_ui!(@root_style (state: $state) ($mode $assets $clone $parent $cmds) $( $tree )+)
+    );
+    (@attrs $mode:tt $assets:tt $clone:tt $parent:tt $cmds:tt #[camera($camera:expr)] $( $tree:tt )+) => (
+        build_ui!(@root_style (camera: $camera) ($mode $assets $clone $parent $cmds) $( $tree )+)
+    );
+    // Add the style modifier of `#[state(..)]` or `#[camera(..)]` to the root
+    (@root_style ($( $modifier:tt )*) ($( $attrs:tt )*) $( #[$( $attr:tt )*] )*
+        $preset:ident $( {$($styles:tt)*} )? $( [$($components:tt)*] )? $( ($($children_list:tt)*) )? $(as $name:ident)?
+    ) => (
+        build_ui!(@attrs $( $attrs )* $( #[$( $attr )*] )*
+            $preset {$( $modifier )*, $($($styles)*)?} $([$($components)*])? $(($($children_list)*))? $(as $name)?
+        )
+    );
+    (@root_style ($key:ident : $value:expr) $attrs:tt $( $tree:tt )+) => (
+        compile_error!(concat!(
+            "`#[", stringify!($key), "(..)]` requires a single root, add `", stringify!($key),
+            ": ..` to the style modifiers of each root instead"
+        ))
+    );
+    (@attrs $mode:tt $assets:tt $clone:tt $parent:tt (world $world:expr) $( $tree:tt )+) => (
+        $crate::__names!(($mode $assets $clone (world) $parent #[cmd($world)] $( $tree )+) ($( $tree )+))
+    );
+    (@attrs $mode:tt $assets:tt $clone:tt $parent:tt ($cmds:expr) $( $tree:tt )+) => (
+        $crate::__names!(($mode $assets $clone () $parent #[cmd($cmds)] $( $tree )+) ($( $tree )+))
+    );
+    (#[cmd($cmds:expr)] $( $tree:tt )+) => ( build_ui!(@attrs () () (clone) () ($cmds) $( $tree )+) );
+    (#[world($world:expr)] $( $tree:tt )+) => ( build_ui!(@attrs () () (clone) () (world $world) $( $tree )+) );
+    // Spawn the roots, as children of the `#[parent(..)]` if any
+    (@root () $opts:tt #[cmd($cmds:expr)]
+        $preset:ident $( {$($styles:tt)*} )? $( [$($components:tt)*] )? $( ($($children_list:tt)*) )? $(as $name:ident)?
+    ) => (
+        build_ui!(@node $opts #[cmd($cmds)]
+            $preset $({$($styles)*})? $([$($components)*])? $(($($children_list)*))? $(as $name)?
         )
     );
-    // if with tail
-    (@child_list list: (if ($predicate:expr) { $( $if_true:tt )* } , $( $tail:tt )+),
-        cmds: $cmds:expr, prefix: ($( $prefix:tt )*),
+    (@root () $opts:tt #[cmd($cmds:expr)]
+        ( $preset:expr ) $( {$($styles:tt)*} )? $( [$($components:tt)*] )? $( ($($children_list:tt)*) )? $(as $name:ident)?
+    ) => ({
+        let __build_ui_preset = $preset;
+        build_ui!(@root () $opts #[cmd($cmds)]
+            __build_ui_preset $({$($styles)*})? $([$($components)*])? $(($($children_list)*))? $(as $name)?
+        )
+    });
+    (@root () $opts:tt #[cmd($cmds:expr)] $( $roots:tt )*) => ({
+        build_ui!(@child_list list: ($( $roots )*), cmds: $cmds, opts: $opts);
+    });
+    (@root ($parent:expr) {collect: $collect:tt, assets: $assets:tt, clone: $clone:tt, world: ()}
+        #[cmd($cmds:expr)] $( $roots:tt )*
+    ) => (
+        $cmds.entity($parent).with_children(|cmds| {
+            build_ui!(@child_list
+                list: ($( $roots )*),
+                cmds: cmds,
+                opts: {collect: $collect, assets: $assets, clone: $clone, world: ()},
+            );
+        })
+    );
+    (@root ($parent:expr) {collect: $collect:tt, assets: $assets:tt, clone: $clone:tt, world: (world)}
+        #[cmd($world:expr)] $( $roots:tt )*
     ) => (
-        build_ui! ( @child_list
-            list: ( $( $tail )+ ),
-            cmds: $cmds,
-            prefix: ($( $prefix )*
-                if $predicate {
-                    build_ui!(@child_list list: ($( $if_true )*), cmds: $cmds, prefix: (),);
+        $world.entity_mut($parent).with_children(|world| {
+            build_ui!(@child_list
+                list: ($( $roots )*),
+                cmds: world,
+                opts: {collect: $collect, assets: $assets, clone: $clone, world: (world)},
+            );
+        })
+    );
+    (@spawn {collect: (), $( $opts:tt )*} $cmds:expr, $bundle:expr) => ( $cmds.spawn($bundle) );
+    (@spawn {collect: ($spawned:ident), $( $opts:tt )*} $cmds:expr, $bundle:expr) => ({
+        let entity = $cmds.spawn($bundle);
+        $spawned.push(entity.id());
+        entity
+    });
+    (@node {collect: $collect:tt, assets: $assets:tt, clone: $clone:tt, world: (world)} #[cmd($cmds:expr)] ids ( $ids:expr )) => (
+        compile_error!("`ids(..)` children are not supported with `#[world(..)]`")
+    );
+    (@node {collect: $collect:tt, assets: $assets:tt, clone: $clone:tt, world: (world)} #[cmd($cmds:expr)] $( #[colored] )? id ( $id:expr ) $( $rest:tt )*) => (
+        compile_error!("`id(..)` children are not supported with `#[world(..)]`")
+    );
+    (@node $opts:tt #[cmd($cmds:expr)] ids ( $ids:expr )) => (
+        for child in $ids {
+            build_ui!(@node $opts #[cmd($cmds)] id (child));
+        }
+    );
+    (@node $opts:tt #[cmd($cmds:expr)] id ( $id:expr ) {$($styles:tt)*} $( $rest:tt )*) => (
+        build_ui!(@colors (@node $opts #[cmd($cmds)] #[colored] id ($id)) ($( $rest )*) [] [] [() ()] () $($styles)*)
+    );
+    // Patch the style, insert the components and spawn the children of an existing entity
+    (@node $opts:tt #[cmd($cmds:expr)] $( #[colored] )? id ( $id:expr )
+        $( {$($styles:tt)*} )?
+        $( [$($bundles:expr),* ; $($components:expr),* $(,)?] )?
+        $( ( $( $children_list:tt )* ) )?
+    ) => ({
+        let child = $id;
+        // Only children lists have a parent entity, where `child` can be mounted
+        let _ = $cmds.parent_entity();
+        // Reserve the place of `child` among its siblings, it takes it once they are pushed
+        let mut reserved = $cmds.spawn_empty();
+        reserved.observe(move |
+            trigger: bevy::ecs::observer::Trigger<bevy::ecs::world::OnAdd, bevy::hierarchy::Parent>,
+            mut commands: bevy::ecs::system::Commands,
+        | {
+            let reserved = trigger.entity();
+            commands.add(move |world: &mut bevy::ecs::world::World| {
+                let parent = world.get::<bevy::hierarchy::Parent>(reserved).map(|parent| parent.get());
+                let children = parent.and_then(|parent| world.get::<bevy::hierarchy::Children>(parent));
+                let index = children.and_then(|children| children.iter().position(|entity| *entity == reserved));
+                if let (Some(parent), Some(index)) = (parent, index) {
+                    world.entity_mut(parent).remove_children(&[reserved]).insert_children(index, &[child]);
+                }
+                world.despawn(reserved);
+            });
+        });
+        let mut commands = reserved.commands();
+        let mut entity = commands.entity(child);
+        $(
+            let patch = style_patch!($($styles)*);
+            entity.add(move |mut entity: bevy::ecs::world::EntityWorldMut| {
+                if let Some(mut style) = entity.get_mut::<bevy::ui::Style>() {
+                    patch.apply(&mut style);
                 }
-            ),
+            });
+        )?
+        $(
+            entity.insert(($(build_ui!(@clone $opts $bundles),)* $(build_ui!(@clone $opts $components),)*));
+        )?
+        $(
+            entity.with_children(|cmds| {
+                build_ui!(@child_list
+                    list: ( $( $children_list )* ),
+                    cmds: cmds,
+                    opts: $opts,
+                );
+            });
+        )?
+    });
+    (@node $opts:tt #[cmd($cmds:expr)] $( #[colored] )? id ( $id:expr ) $( $rest:tt )*) => (
+        compile_error!("`id(..)` components are only plain components, without `if`, `?`, `move` or observers")
+    );
+    (@batch {collect: ($spawned:ident), $( $opts:tt )*} $( $batch:tt )*) => (
+        compile_error!("`batch(..)` entities can't be collected with `#[collect]`")
+    );
+    (@batch {collect: $collect:tt, assets: $assets:tt, clone: $clone:tt, world: (world)} $( $batch:tt )*) => (
+        compile_error!("`batch(..)` children are not supported with `#[world(..)]`")
+    );
+    (@batch $opts:tt #[cmd($cmds:expr)] ($count:expr) $preset:ident
+        $( {$($styles:tt)*} )? $( [$($bundles:expr),* ; $($components:expr),* $(,)?] )?
+    ) => ({
+        let count = $count;
+        let bundle = (
+            $crate::__named!(($preset) build_ui!(@preset $opts $preset $({$($styles)*})?)),
+            $($( build_ui!(@clone $opts $bundles), )* $( build_ui!(@clone $opts $components), )*)?
+        );
+        // Reserve the children now, so that they keep their place among their siblings
+        let children: Vec<_> = (0..count).map(|_| $cmds.spawn_empty().id()).collect();
+        $cmds.add_command(move |world: &mut bevy::ecs::world::World| {
+            let _ = world.insert_or_spawn_batch(children.iter().copied().zip(std::iter::repeat(bundle)));
+            build_ui!(@batch_clickable $preset world children);
+        });
+    });
+    (@batch_clickable button $world:ident $children:ident) => (
+        for child in $children {
+            let mut entity = $world.entity_mut(child);
+            build_ui!(@clickable entity);
+        }
+    );
+    (@batch_clickable $preset:ident $world:ident $children:ident) => ();
+    (@node $opts:tt #[cmd($cmds:expr)] $preset:ident
+        $( {$($styles:tt)*} )? $( [$($components:tt)*] )? $( ($($children_list:tt)*) )?
+        as $name:ident
+    ) => (
+        $name = build_ui!(@node $opts #[cmd($cmds)] #[name($name)] $preset
+            $({$($styles)*})? $([$($components)*])? $(($($children_list)*))?
+        ).id()
+    );
+    (@node $opts:tt #[cmd($cmds:expr)] $( #[name($name:ident)] )? this $( [$($components:tt)*] )? $( ($($children_list:tt)*) )?) => (
+        build_ui!(@extend $opts ($cmds) $([$($components)*])? $(($($children_list)*))?)
+    );
+    // Split the `background` and `border_color` out of the style modifiers
+    (@node $opts:tt #[cmd($cmds:expr)] $( #[name($name:ident)] )? $kind:ident {$($styles:tt)*} $( $rest:tt )*) => (
+        build_ui!(@colors (@node $opts #[cmd($cmds)] $( #[name($name)] )? #[colored] $kind) ($( $rest )*) [] [] [() ()] () $($styles)*)
+    );
+    (@node $opts:tt #[cmd($cmds:expr)] $( #[name($name:ident)] )? $( #[colored] )?
+        img $( {$($styles:tt)*} )? $( [$($components:tt)*] )? ($image:expr)
+    ) => (
+        build_ui!(@insert $opts #[cmd($cmds)] (img $($name)?) (build_ui!(@img $opts $({$($styles)*})? $image)) $([$($components)*])?)
+    );
+    (@node $opts:tt #[cmd($cmds:expr)] $( #[name($name:ident)] )? $( #[colored] )?
+        text $( {$($styles:tt)*} )? $( [$($components:tt)*] )? ($($text:tt)*)
+    ) => (
+        build_ui!(@insert $opts #[cmd($cmds)] (text $($name)?) (build_ui!(@text $({$($styles)*})? $($text)*)) $([$($components)*])?)
+    );
+    (@node $opts:tt #[cmd($cmds:expr)] $( #[name($name:ident)] )? $( #[colored] )?
+        overlay $( {$($styles:tt)*} )? $( [$($components:tt)*] )? $( ($($children_list:tt)*) )?
+    ) => (
+        build_ui!(@insert $opts #[cmd($cmds)] (overlay $($name)?)
+            (build_ui!(@overlay {$($($styles)*)?}))
+            $([$($components)*])? $(($($children_list)*))?
         )
     );
-    // just terminal
-    (@child_list list: ($preset:ident $( { $($syl:tt)* } )? $( [ $($bc:tt)* ] )? $( ( $( $c:tt )* ) )? $(,)?),
-        cmds: $cmds:expr, prefix: ($( $prefix:tt )*),
+    (@node $opts:tt #[cmd($cmds:expr)] $( #[name($name:ident)] )? $( #[colored] )? $preset:ident
+        $( {$($styles:tt)*} )? // {..} style modifiers
+        $( [$($components:tt)*] )? // [..] components
+        $( ( $( $children_list:tt )* ) )?
+    ) => (
+        build_ui!(@preset_node $preset
+            ($opts #[cmd($cmds)] ($preset $($name)?) (build_ui!(@preset $opts $preset $({$($styles)*})?)))
+            $([$($components)*])? $(( $( $children_list )* ))?
+        )
+    );
+    // `button` also gets the components making it clickable, unless its preset
+    // already is a button
+    (@preset_node button ($( $insert:tt )*) [$($bundles:expr),* ; $($components:tt)*] $( $children:tt )?) => (
+        build_ui!(@insert $( $insert )* [$($bundles),* ; @clickable, $($components)*] $( $children )?)
+    );
+    (@preset_node button $insert:tt $( $children:tt )?) => (
+        build_ui!(@preset_node button $insert [;] $( $children )?)
+    );
+    (@preset_node $preset:ident ($( $insert:tt )*) $( $rest:tt )*) => (
+        build_ui!(@insert $( $insert )* $( $rest )*)
+    );
+    // Split the comma-separated style modifiers, the colors are inserted as
+    // components rather than set in the `Style`, the `role` and `label` are
+    // gathered in a single `AccessibilityNode`
+    (@colors ($( $node:tt )*) ($( $rest:tt )*) [$( $styles:tt )*] [] [() ()] ()) => (
+        build_ui!($( $node )* {$( $styles )*} $( $rest )*)
+    );
+    (@colors ($( $node:tt )*) ([$($bundles:expr),* ; $($components:tt)*] $( $rest:tt )*) [$( $styles:tt )*] [$( $colors:tt )+] [() ()] ()) => (
+        build_ui!($( $node )* {$( $styles )*} [$($bundles),* ; $( $colors )+ $($components)*] $( $rest )*)
+    );
+    (@colors ($( $node:tt )*) ($( $rest:tt )*) [$( $styles:tt )*] [$( $colors:tt )+] [() ()] ()) => (
+        build_ui!($( $node )* {$( $styles )*} [; $( $colors )+] $( $rest )*)
+    );
+    (@colors $node:tt $rest:tt $styles:tt [$( $colors:tt )*] [($( $role:ident )?) ($( $label:expr )?)] ()) => (
+        build_ui!(@colors $node $rest $styles [$( $colors )* build_ui!(@a11y ($( $role )?) ($( $label )?)),] [() ()] ())
+    );
+    (@colors $node:tt $rest:tt $styles:tt $colors:tt $a11y:tt ($( $entry:tt )+)) => (
+        build_ui!(@color $node $rest $styles $colors $a11y ($( $entry )+))
+    );
+    (@colors $node:tt $rest:tt $styles:tt $colors:tt $a11y:tt ($( $entry:tt )*) , $( $tail:tt )*) => (
+        build_ui!(@color $node $rest $styles $colors $a11y ($( $entry )*) $( $tail )*)
+    );
+    (@colors $node:tt $rest:tt $styles:tt $colors:tt $a11y:tt ($( $entry:tt )*) $next:tt $( $tail:tt )*) => (
+        build_ui!(@colors $node $rest $styles $colors $a11y ($( $entry )* $next) $( $tail )*)
+    );
+    (@color $node:tt $rest:tt $styles:tt $colors:tt $a11y:tt () $( $tail:tt )*) => (
+        build_ui!(@colors $node $rest $styles $colors $a11y () $( $tail )*)
+    );
+    (@color $node:tt $rest:tt $styles:tt [$( $colors:tt )*] $a11y:tt (background : $color:expr) $( $tail:tt )*) => (
+        build_ui!(@colors $node $rest $styles [$( $colors )* bevy::ui::BackgroundColor::from($color),] $a11y () $( $tail )*)
+    );
+    (@color $node:tt $rest:tt $styles:tt [$( $colors:tt )*] $a11y:tt (border_color : $color:expr) $( $tail:tt )*) => (
+        build_ui!(@colors $node $rest $styles [$( $colors )* bevy::ui::BorderColor::from($color),] $a11y () $( $tail )*)
+    );
+    (@color $node:tt $rest:tt $styles:tt [$( $colors:tt )*] $a11y:tt (visibility : $visibility:ident) $( $tail:tt )*) => (
+        build_ui!(@colors $node $rest $styles [$( $colors )* bevy::render::view::Visibility::$visibility,] $a11y () $( $tail )*)
+    );
+    (@color $node:tt $rest:tt $styles:tt $colors:tt [() $label:tt] (role : $role:ident) $( $tail:tt )*) => (
+        build_ui!(@colors $node $rest $styles $colors [($role) $label] () $( $tail )*)
+    );
+    (@color $node:tt $rest:tt $styles:tt $colors:tt [$role:tt ()] (label : $label:expr) $( $tail:tt )*) => (
+        build_ui!(@colors $node $rest $styles $colors [$role ($label)] () $( $tail )*)
+    );
+    (@color $node:tt $rest:tt $styles:tt [$( $colors:tt )*] $a11y:tt (camera : $camera:expr) $( $tail:tt )*) => (
+        build_ui!(@colors $node $rest $styles [$( $colors )* bevy::ui::TargetCamera($camera),] $a11y () $( $tail )*)
+    );
+    (@color $node:tt $rest:tt $styles:tt [$( $colors:tt )*] $a11y:tt (state : $state:expr) $( $tail:tt )*) => (
+        build_ui!(@colors $node $rest $styles [$( $colors )* bevy::state::state_scoped::StateScoped($state),] $a11y () $( $tail )*)
+    );
+    (@color $node:tt $rest:tt $styles:tt [$( $colors:tt )*] $a11y:tt (z : global $z:expr) $( $tail:tt )*) => (
+        build_ui!(@colors $node $rest $styles [$( $colors )* bevy::ui::ZIndex::Global($z),] $a11y () $( $tail )*)
+    );
+    (@color $node:tt $rest:tt $styles:tt [$( $colors:tt )*] $a11y:tt (z : $z:expr) $( $tail:tt )*) => (
+        build_ui!(@colors $node $rest $styles [$( $colors )* bevy::ui::ZIndex::Local($z),] $a11y () $( $tail )*)
+    );
+    (@color $node:tt $rest:tt $styles:tt [$( $colors:tt )*] $a11y:tt (radius : $( $radius:tt )+) $( $tail:tt )*) => (
+        build_ui!(@colors $node $rest $styles [$( $colors )* bevy::ui::BorderRadius::all(unit!($( $radius )+)),] $a11y () $( $tail )*)
+    );
+    (@color $node:tt $rest:tt [$( $styles:tt )*] $colors:tt $a11y:tt ($( $entry:tt )*) $( $tail:tt )*) => (
+        build_ui!(@colors $node $rest [$( $styles )* $( $entry )*,] $colors $a11y () $( $tail )*)
+    );
+    (@a11y ($( $role:ident )?) ($( $label:expr )?)) => ({
+        let mut node = bevy::a11y::accesskit::NodeBuilder::new(build_ui!(@role $( $role )?));
+        $( node.set_name($label); )?
+        bevy::a11y::AccessibilityNode(node)
+    });
+    (@role $role:ident) => (bevy::a11y::accesskit::Role::$role);
+    (@role) => (bevy::a11y::accesskit::Role::Unknown);
+    (@overlay {$($styles:tt)*}) => (
+        bevy::ui::node_bundles::NodeBundle {
+            style: style! {
+                ..bevy::ui::Style {
+                    position_type: bevy::ui::PositionType::Absolute,
+                    width: bevy::ui::Val::Percent(100.0),
+                    height: bevy::ui::Val::Percent(100.0),
+                    ..Default::default()
+                },
+                $($styles)*
+            },
+            z_index: bevy::ui::ZIndex::Global(100),
+            .. Default::default()
+        }
+    );
+    (@img $opts:tt {$($styles:tt)*} $image:expr) => (
+        bevy::ui::node_bundles::ImageBundle {
+            style: style!($($styles)*),
+            .. build_ui!(@img $opts $image)
+        }
+    );
+    (@img {collect: $collect:tt, assets: ($assets:expr), $( $opts:tt )*} $image:expr) => (
+        bevy::ui::node_bundles::ImageBundle {
+            image: $assets.load($image).into(),
+            .. Default::default()
+        }
+    );
+    (@img {collect: $collect:tt, assets: (), $( $opts:tt )*} $image:expr) => (
+        bevy::ui::node_bundles::ImageBundle {
+            image: $image.into(),
+            .. Default::default()
+        }
+    );
+    (@text {$($styles:tt)*} $($text:tt)*) => (
+        bevy::ui::node_bundles::TextBundle {
+            style: style!($($styles)*),
+            .. build_ui!(@text $($text)*)
+        }
+    );
+    (@text [$($spans:tt)*] $(,)?) => (
+        bevy::ui::node_bundles::TextBundle::from_sections(build_ui!(@spans [] () $($spans)*))
+    );
+    (@text $text:expr $(, $($param:ident : $value:expr),* $(,)?)?) => (
+        bevy::ui::node_bundles::TextBundle::from_section($text, {
+            #[allow(unused_mut)]
+            let mut style = bevy::text::TextStyle::default();
+            $($( build_ui!(@text_param style $param $value); )*)?
+            style
+        })
+    );
+    // Split the comma-separated text spans
+    (@spans [$($sections:tt)*] ()) => ( [$($sections)*] );
+    (@spans [$($sections:tt)*] ($($span:tt)+)) => ( [$($sections)* build_ui!(@span () $($span)+)] );
+    (@spans [$($sections:tt)*] ($($span:tt)+) , $($tail:tt)*) => (
+        build_ui!(@spans [$($sections)* build_ui!(@span () $($span)+),] () $($tail)*)
+    );
+    (@spans [$($sections:tt)*] ($($span:tt)*) $next:tt $($tail:tt)*) => (
+        build_ui!(@spans [$($sections)*] ($($span)* $next) $($tail)*)
+    );
+    // A span is a value, optionally followed by the name of its `TextStyle`
+    (@span () $value:expr) => ( bevy::text::TextSection::new($value, Default::default()) );
+    (@span ($($value:tt)+) $style:ident) => ( bevy::text::TextSection::new($($value)+, $style.clone()) );
+    (@span ($($value:tt)*) $next:tt $($tail:tt)+) => ( build_ui!(@span ($($value)* $next) $($tail)+) );
+    // Add the components to the entity commands, one entry at a time, the
+    // inserted components are collected in a single tuple bundle, observers
+    // and conditional components are collected as statements on the entity
+    (@components $opts:tt ($($entity:tt)*) [] {} $(,)?) => ( $($entity)* );
+    (@components $opts:tt ($($entity:tt)*) [$($inserted:tt)+] {} $(,)?) => ( $($entity)*.insert(($($inserted)+)) );
+    (@components $opts:tt ($($entity:tt)*) [$($inserted:tt)*] {$( $statements:tt )+} $(,)?) => ({
+        let mut entity = $($entity)*;
+        build_ui!(@statement entity (insert [$($inserted)*]));
+        $( build_ui!(@statement entity $statements); )+
+        entity
+    });
+    (@components $opts:tt $entity:tt $inserted:tt {$( $statements:tt )*}
+        $event:ident : |$trigger:pat_param $(, $($param:ident)+ $(: $type:ty)?)*| $handler:expr
+        $(, $($tail:tt)*)?
     ) => (
-        $( $prefix )*
-        build_ui!{ #[cmd($cmds)] $preset $( { $($syl)* } )? $( [ $($bc)* ] )? $( ( $($c)* ) )? }
-    );
-    // just has a tail
-    (@child_list list: (
-            $preset:ident $( { $($syl:tt)* } )? $( [ $($bc:tt)* ] )? $( ( $( $c:tt )* ) )?
-            , $( $tail:tt )+
-        ),
-        cmds: $cmds:expr, prefix: ($( $prefix:tt )*),
+        build_ui!(@components $opts $entity $inserted {$( $statements )*
+            (observe move |
+                $trigger: bevy::ecs::observer::Trigger<bevy::picking::events::Pointer<build_ui!(@pointer_event $event)>>
+                $(, $($param)+ : build_ui!(@param_type $($type)?))*
+            | $handler)
+        } $($($tail)*)?)
+    );
+    // Tooltips need bevy 0.15 picking, which removed `TextBundle` and `ImageBundle`
+    (@components $opts:tt $entity:tt $inserted:tt $statements:tt tooltip(text $($tooltip:tt)*) $( $tail:tt )*) => (
+        build_ui!(@tooltip_leaf text)
+    );
+    (@components $opts:tt $entity:tt $inserted:tt $statements:tt tooltip(img $($tooltip:tt)*) $( $tail:tt )*) => (
+        build_ui!(@tooltip_leaf img)
+    );
+    (@tooltip_leaf $leaf:ident) => (
+        compile_error!(concat!(
+            "`tooltip(", stringify!($leaf), "(..))` is not supported, tooltips require bevy 0.15, ",
+            "which removed `TextBundle` and `ImageBundle`, use a preset instead: `tooltip(hint)`"
+        ))
+    );
+    (@components $opts:tt $entity:tt $inserted:tt {$( $statements:tt )*} tooltip($($tooltip:tt)+) $(, $($tail:tt)*)?) => (
+        build_ui!(@components $opts $entity $inserted {$( $statements )* (tooltip $opts $($tooltip)+)} $($($tail)*)?)
+    );
+    (@components $opts:tt $entity:tt $inserted:tt {$( $statements:tt )*} @clickable $(, $($tail:tt)*)?) => (
+        build_ui!(@components $opts $entity $inserted {$( $statements )* (clickable $opts)} $($($tail)*)?)
+    );
+    (@components $opts:tt $entity:tt $inserted:tt {$( $statements:tt )*} observe($observer:expr) $(, $($tail:tt)*)?) => (
+        build_ui!(@components $opts $entity $inserted {$( $statements )* (observe $observer)} $($($tail)*)?)
+    );
+    (@components $opts:tt $entity:tt $inserted:tt {$( $statements:tt )*}
+        if $predicate:expr => $component:expr $(, $($tail:tt)*)?
     ) => (
-        build_ui! ( @child_list
-            list: ( $( $tail )+ ),
-            cmds: $cmds,
-            prefix: ($( $prefix )*
-                build_ui!{ #[cmd($cmds)] $preset $( { $($syl)* } )? $( [ $($bc)* ] )? $( ( $($c)* ) )? };
-            ),
+        build_ui!(@components $opts $entity $inserted
+            {$( $statements )* (insert if $predicate => build_ui!(@clone $opts $component))}
+            $($($tail)*)?
+        )
+    );
+    (@components $opts:tt $entity:tt $inserted:tt {$( $statements:tt )*} $($option:ident).+ ? $(, $($tail:tt)*)?) => (
+        build_ui!(@components $opts $entity $inserted
+            {$( $statements )* (insert some build_ui!(@clone $opts $($option).+))}
+            $($($tail)*)?
         )
     );
-    (#[cmd($cmds:expr)] id ( $id:expr )) => ({
-        use bevy::ecs::system::Insert;
-        let parent = $cmds.parent_entity();
-        let insert = bevy::hierarchy::AddChild {
-            child: $id,
-            parent,
-        };
-        $cmds.add_command(insert);
+    (@components $opts:tt $entity:tt $inserted:tt {$( $statements:tt )*} ($option:expr) ? $(, $($tail:tt)*)?) => (
+        build_ui!(@components $opts $entity $inserted
+            {$( $statements )* (insert some build_ui!(@clone $opts $option))}
+            $($($tail)*)?
+        )
+    );
+    (@components $opts:tt $entity:tt [$($inserted:tt)*] $statements:tt move $component:expr $(, $($tail:tt)*)?) => (
+        build_ui!(@components $opts $entity [$($inserted)* $component,] $statements $($($tail)*)?)
+    );
+    (@components $opts:tt $entity:tt [$($inserted:tt)*] $statements:tt $component:expr $(, $($tail:tt)*)?) => (
+        build_ui!(@components $opts $entity [$($inserted)* build_ui!(@clone $opts $component),] $statements $($($tail)*)?)
+    );
+    (@statement $entity:ident (insert [])) => ( () );
+    (@statement $entity:ident (insert [$($inserted:tt)+])) => ( $entity.insert(($($inserted)+)) );
+    (@statement $entity:ident (insert some $option:expr)) => (
+        if let Some(component) = $option {
+            $entity.insert(component);
+        }
+    );
+    (@statement $entity:ident (observe $observer:expr)) => ( $entity.observe($observer) );
+    // Make `$entity` clickable once its preset is inserted, if it isn't a button already
+    (@statement $entity:ident (clickable {collect: $collect:tt, assets: $assets:tt, clone: $clone:tt, world: (world)})) => (
+        build_ui!(@clickable $entity)
+    );
+    (@statement $entity:ident (clickable $opts:tt)) => (
+        $entity.add(|mut entity: bevy::ecs::world::EntityWorldMut| build_ui!(@clickable entity))
+    );
+    (@clickable $entity:ident) => (
+        if !$entity.contains::<bevy::ui::widget::Button>() {
+            $entity.insert((bevy::ui::widget::Button, bevy::ui::Interaction::default(), bevy::ui::FocusPolicy::Block));
+        }
+    );
+    // Spawn the tooltip as a hidden child, shown while the pointer is over `$entity`
+    (@statement $entity:ident (tooltip $opts:tt $($tooltip:tt)+)) => ({
+        let mut tooltip = bevy::ecs::entity::Entity::from_raw(u32::MAX);
+        $entity.with_children(|cmds| {
+            tooltip = build_ui!(@node $opts #[cmd(cmds)] $($tooltip)+)
+                .insert(bevy::render::view::Visibility::Hidden)
+                .id();
+        });
+        $entity.observe(move |
+            _: bevy::ecs::observer::Trigger<bevy::picking::events::Pointer<bevy::picking::events::Over>>,
+            mut commands: bevy::ecs::system::Commands,
+        | {
+            commands.entity(tooltip).insert(bevy::render::view::Visibility::Inherited);
+        });
+        $entity.observe(move |
+            _: bevy::ecs::observer::Trigger<bevy::picking::events::Pointer<bevy::picking::events::Out>>,
+            mut commands: bevy::ecs::system::Commands,
+        | {
+            commands.entity(tooltip).insert(bevy::render::view::Visibility::Hidden);
+        });
     });
-    (#[cmd($cmds:expr)] $preset:ident
-        $( {$($styles:tt)*} )? // {..} style modifiers
-        $( [$($bundles:expr),* ; $($components:expr),*] )? // [..] components
+    (@statement $entity:ident (insert if $predicate:expr => $component:expr)) => (
+        if $predicate {
+            $entity.insert($component);
+        }
+    );
+    (@pointer_event on_click) => ( bevy::picking::events::Click );
+    (@pointer_event on_over) => ( bevy::picking::events::Over );
+    (@pointer_event on_out) => ( bevy::picking::events::Out );
+    (@pointer_event on_drag) => ( bevy::picking::events::Drag );
+    (@pointer_event $event:ident) => (
+        compile_error!(concat!("unknown pointer event `", stringify!($event), "`, expected `on_click`, `on_over`, `on_out` or `on_drag`"))
+    );
+    (@param_type) => ( bevy::ecs::system::Commands );
+    (@param_type $type:ty) => ( $type );
+    (@text_param $style:ident font $value:expr) => ( $style.font = $value );
+    (@text_param $style:ident size $value:expr) => ( $style.font_size = $value as f32 );
+    (@text_param $style:ident color $value:expr) => ( $style.color = $value );
+    (@text_param $style:ident $param:ident $value:expr) => (
+        compile_error!(concat!("unknown text parameter `", stringify!($param), "`, expected `font`, `size` or `color`"))
+    );
+    (@insert $opts:tt #[cmd($cmds:expr)] $names:tt ($bundle:expr) $( $rest:tt )*) => (
+        $crate::__traced!($names build_ui!(@extend $opts
+            (build_ui!(@spawn $opts $cmds, $crate::__named!($names $bundle))) $( $rest )*
+        ))
+    );
+    // Add components and children to an entity
+    (@extend $opts:tt $entity:tt
+        $( [$($bundles:expr),* ; $($components:tt)*] )? // [..] components
         $( ( $( $children_list:tt )* ) )?
     ) => (
-        $cmds.spawn(build_ui!(@preset $preset $({$($styles)*})?).clone())
-            $($(.insert($bundles.clone()))*
-            $(.insert($components.clone()))*)?
+        build_ui!(@components $opts
+            $entity
+            [$($(build_ui!(@clone $opts $bundles),)*)?]
+            {}
+            $($($components)*)?
+        )
             $(.with_children(|cmds| {
                 build_ui!(@child_list
                     list: ( $( $children_list )* ),
                     cmds: cmds,
-                    prefix: (),
+                    opts: $opts,
                 );
             }))?
     );
 }
+
+/// Declare reusable `build_ui!` subtrees, with parameters and slots
+///
+/// Each template becomes a function spawning its tree as children of the
+/// given `ChildBuilder`. Parameters of type `slot` are children lists provided
+/// by the caller, spawned where the tree has `slot!(name)`:
+///
+/// ```rust,ignore
+/// define_template! {
+///     pub labeled_row(label: &str, content: slot) {
+///         row(text(label.to_owned()), slot!(content))
+///     }
+/// }
+/// // Is strictly equivalent to
+/// pub fn labeled_row(
+///     cmds: &mut bevy::hierarchy::ChildBuilder,
+///     label: &str,
+///     content: impl FnOnce(&mut bevy::hierarchy::ChildBuilder),
+/// ) {
+///     build_ui! { #[cmd(cmds)] row(text(label.to_owned()), slot!(content)) };
+/// }
+/// ```
+///
+/// Templates are called in `build_ui!` children lists with a `!`, followed by
+/// the children of each slot, in the order of the parameters:
+/// `vertical(labeled_row!("Volume")(slider), labeled_row!("Music")(toggle))`.
+/// Each slot is spawned at most once.
+#[macro_export]
+macro_rules! define_template {
+    (@params [$( $params:tt )*] () $attrs:tt $vis:vis $name:ident $tree:tt) => (
+        define_template!(@define [$( $params )*] $attrs $vis $name $tree);
+    );
+    (@params [$( $params:tt )*] ($param:ident : slot $(, $( $tail:tt )*)?) $( $rest:tt )*) => (
+        define_template!(@params
+            [$( $params )* $param: impl FnOnce(&mut bevy::hierarchy::ChildBuilder),]
+            ($($( $tail )*)?) $( $rest )*
+        );
+    );
+    (@params [$( $params:tt )*] ($param:ident : $type:ty $(, $( $tail:tt )*)?) $( $rest:tt )*) => (
+        define_template!(@params [$( $params )* $param: $type,] ($($( $tail )*)?) $( $rest )*);
+    );
+    (@define [$( $params:tt )*] [$( $attrs:tt )*] $vis:vis $name:ident { $( $tree:tt )* }) => (
+        $( $attrs )*
+        $vis fn $name(cmds: &mut bevy::hierarchy::ChildBuilder, $( $params )*) {
+            build_ui! { #[cmd(cmds)] $( $tree )* };
+        }
+    );
+    ($( $(#[$attr:meta])* $vis:vis $name:ident ( $( $params:tt )* ) $tree:tt )*) => {
+        $(
+            define_template!(@params [] ($( $params )*) [$( #[$attr] )*] $vis $name $tree);
+        )*
+    };
+}
+
+/// Declare `build_ui!` children lists usable in other `build_ui!`s
+///
+/// Each fragment becomes a macro, spliced in `build_ui!` children lists with
+/// `include name`, to split large UIs across modules and files:
+///
+/// ```rust,ignore
+/// ui_fragment! {
+///     audio_settings {
+///         text("Volume"), slider, text("Music"), toggle
+///     }
+/// }
+/// build_ui! {
+///     #[cmd(commands)]
+///     vertical(include audio_settings, include video_settings)
+/// }
+/// // Is strictly equivalent to
+/// build_ui! {
+///     #[cmd(commands)]
+///     vertical(text("Volume"), slider, text("Music"), toggle, include video_settings)
+/// }
+/// ```
+///
+/// Like any `macro_rules!`, a fragment can only be used after its declaration,
+/// add `#[macro_export]` to use it in other modules. Because of macro hygiene,
+/// fragments can refer to items, such as constants and `define_style!` presets,
+/// but not to the local variables of the `build_ui!` including them. The `as
+/// name` of fragment entities are not declared by `build_ui!`.
+#[macro_export]
+macro_rules! ui_fragment {
+    (@define ($d:tt) $( $(#[$attr:meta])* $name:ident { $( $tree:tt )* } )*) => {
+        $(
+            $(#[$attr])*
+            macro_rules! $name {
+                ($d cmds:expr, $d opts:tt) => {
+                    build_ui!(@child_list list: ($( $tree )*), cmds: $d cmds, opts: $d opts);
+                };
+            }
+        )*
+    };
+    ($( $fragments:tt )*) => { ui_fragment!(@define ($) $( $fragments )*); };
+}
+
+/// Add a `Name` to the bundle of a `build_ui!` entity, from its `as name` if
+/// any, or from its preset otherwise.
+#[cfg(feature = "names")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __named {
+    ((__build_ui_preset) $bundle:expr) => ( ($bundle, bevy::core::Name::new("preset")) );
+    (($preset:ident) $bundle:expr) => ( ($bundle, bevy::core::Name::new(stringify!($preset))) );
+    (($preset:ident $name:ident) $bundle:expr) => ( ($bundle, bevy::core::Name::new(stringify!($name))) );
+}
+
+#[cfg(not(feature = "names"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __named {
+    ($names:tt $bundle:expr) => ( $bundle );
+}
+
+/// Wrap the spawning of a `build_ui!` entity in a tracing span, named from its
+/// `as name` if any, or from its preset otherwise.
+#[cfg(feature = "trace")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __traced {
+    ((__build_ui_preset) $spawn:expr) => ( $crate::__traced!(@span "preset", $spawn) );
+    (($preset:ident) $spawn:expr) => ( $crate::__traced!(@span stringify!($preset), $spawn) );
+    (($preset:ident $name:ident) $spawn:expr) => ( $crate::__traced!(@span stringify!($name), $spawn) );
+    (@span $span:expr, $spawn:expr) => ({
+        let _span = bevy::log::info_span!($span).entered();
+        $spawn
+    });
+}
+
+#[cfg(not(feature = "trace"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __traced {
+    ($names:tt $spawn:expr) => ( $spawn );
+}
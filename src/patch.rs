@@ -0,0 +1,69 @@
+//! `StylePatch`, the value built by `style_patch!`.
+
+/// A patch changing only some fields of a `bevy::ui::Style`, built with
+/// `style_patch!`
+///
+/// The patch holds a `Style` with the patched values, the other fields are
+/// `Style::default()`, and the list of the patched fields. `S` is always
+/// `bevy::ui::Style`, it is a parameter because this crate doesn't depend on
+/// bevy.
+///
+/// A patch can be cloned and stored in a component or a resource, to apply it
+/// later from a system.
+#[derive(Clone, Debug)]
+pub struct StylePatch<S> {
+    values: S,
+    fields: Vec<(&'static str, CopyField<S>)>,
+}
+
+/// Copies a patched field from the values of a patch to a style.
+type CopyField<S> = fn(&mut S, &S);
+
+impl<S: Default> Default for StylePatch<S> {
+    /// A patch changing no fields.
+    fn default() -> Self {
+        Self::new(S::default())
+    }
+}
+
+impl<S> StylePatch<S> {
+    #[doc(hidden)]
+    pub fn new(values: S) -> Self {
+        StylePatch {
+            values,
+            fields: Vec::new(),
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn patch_field(&mut self, field: &'static str, copy: CopyField<S>) {
+        self.fields.retain(|(patched, _)| *patched != field);
+        self.fields.push((field, copy));
+    }
+
+    /// Set the patched fields of `style` to the values of this patch.
+    pub fn apply(&self, style: &mut S) {
+        for (_, copy) in &self.fields {
+            copy(style, &self.values);
+        }
+    }
+
+    /// Add the fields of `other` to this patch, the values of `other` win for
+    /// the fields both patches change.
+    pub fn merge(&mut self, other: &Self) {
+        for &(field, copy) in &other.fields {
+            copy(&mut self.values, &other.values);
+            self.patch_field(field, copy);
+        }
+    }
+
+    /// The names of the patched fields, such as `"width"`.
+    pub fn fields(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.fields.iter().map(|(field, _)| *field)
+    }
+
+    /// The `Style` holding the patched values.
+    pub fn values(&self) -> &S {
+        &self.values
+    }
+}
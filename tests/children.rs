@@ -0,0 +1,166 @@
+//! The children of a `build_ui!` node keep their declaration order.
+use bevy::prelude::*;
+use bevy_ui_build_macros::*;
+
+#[derive(Component, Clone)]
+struct Tag(u32);
+
+#[derive(Resource)]
+struct Root(Entity);
+
+/// The `Tag`s of the children of the `Root` spawned by `setup`.
+fn children<M>(setup: impl IntoSystemConfigs<M>) -> Vec<u32> {
+    let mut app = App::new();
+    app.add_systems(Startup, setup);
+    app.update();
+    let world = app.world();
+    let root = world.resource::<Root>().0;
+    world.get::<Children>(root).map_or(Vec::new(), |children| {
+        children
+            .iter()
+            .map(|child| world.get::<Tag>(*child).map_or(0, |tag| tag.0))
+            .collect()
+    })
+}
+
+#[test]
+fn batch() {
+    fn setup(mut commands: Commands) {
+        let node = NodeBundle::default();
+        build_ui! {
+            #[cmd(commands)]
+            node(node[; Tag(1)], batch(2) node[; Tag(2)], node[; Tag(3)]) as root
+        };
+        commands.insert_resource(Root(root));
+    }
+    assert_eq!(children(setup), [1, 2, 2, 3]);
+}
+
+/// More siblings than the default `recursion_limit`, with bindings among them
+#[test]
+fn siblings() {
+    fn setup(mut commands: Commands) {
+        let node = NodeBundle::default();
+        build_ui! {
+            #[cmd(commands)]
+            node(
+                node[; Tag(1)], node[; Tag(2)], node[; Tag(3)],
+                node[; Tag(4)], node[; Tag(5)], node[; Tag(6)],
+                node[; Tag(7)], node[; Tag(8)], node[; Tag(9)],
+                let tag = 10; node[; Tag(tag)],
+                node[; Tag(11)], node[; Tag(12)], node[; Tag(13)],
+                node[; Tag(14)], node[; Tag(15)], node[; Tag(16)],
+                node[; Tag(17)], node[; Tag(18)], node[; Tag(19)],
+                let tag = 20; node[; Tag(tag)],
+                node[; Tag(21)], node[; Tag(22)], node[; Tag(23)],
+                node[; Tag(24)], node[; Tag(25)], node[; Tag(26)],
+                node[; Tag(27)], node[; Tag(28)], node[; Tag(29)],
+                let tag = 30; node[; Tag(tag)],
+                node[; Tag(31)], node[; Tag(32)], node[; Tag(33)],
+                node[; Tag(34)], node[; Tag(35)], node[; Tag(36)],
+                node[; Tag(37)], node[; Tag(38)], node[; Tag(39)],
+                let tag = 40; node[; Tag(tag)],
+                node[; Tag(41)], node[; Tag(42)], node[; Tag(43)],
+                node[; Tag(44)], node[; Tag(45)], node[; Tag(46)],
+                node[; Tag(47)], node[; Tag(48)], node[; Tag(49)],
+                let tag = 50; node[; Tag(tag)],
+                node[; Tag(51)], node[; Tag(52)], node[; Tag(53)],
+                node[; Tag(54)], node[; Tag(55)], node[; Tag(56)],
+                node[; Tag(57)], node[; Tag(58)], node[; Tag(59)],
+                let tag = 60; node[; Tag(tag)],
+                node[; Tag(61)], node[; Tag(62)], node[; Tag(63)],
+                node[; Tag(64)], node[; Tag(65)], node[; Tag(66)],
+                node[; Tag(67)], node[; Tag(68)], node[; Tag(69)],
+                let tag = 70; node[; Tag(tag)],
+                node[; Tag(71)], node[; Tag(72)], node[; Tag(73)],
+                node[; Tag(74)], node[; Tag(75)], node[; Tag(76)],
+                node[; Tag(77)], node[; Tag(78)], node[; Tag(79)],
+                let tag = 80; node[; Tag(tag)],
+                node[; Tag(81)], node[; Tag(82)], node[; Tag(83)],
+                node[; Tag(84)], node[; Tag(85)], node[; Tag(86)],
+                node[; Tag(87)], node[; Tag(88)], node[; Tag(89)],
+                let tag = 90; node[; Tag(tag)],
+                node[; Tag(91)], node[; Tag(92)], node[; Tag(93)],
+                node[; Tag(94)], node[; Tag(95)], node[; Tag(96)],
+                node[; Tag(97)], node[; Tag(98)], node[; Tag(99)],
+                let tag = 100; node[; Tag(tag)],
+                node[; Tag(101)], node[; Tag(102)], node[; Tag(103)],
+                node[; Tag(104)], node[; Tag(105)], node[; Tag(106)],
+                node[; Tag(107)], node[; Tag(108)], node[; Tag(109)],
+                let tag = 110; node[; Tag(tag)],
+                node[; Tag(111)], node[; Tag(112)], node[; Tag(113)],
+                node[; Tag(114)], node[; Tag(115)], node[; Tag(116)],
+                node[; Tag(117)], node[; Tag(118)], node[; Tag(119)],
+                let tag = 120; node[; Tag(tag)],
+                node[; Tag(121)], node[; Tag(122)], node[; Tag(123)],
+                node[; Tag(124)], node[; Tag(125)], node[; Tag(126)],
+                node[; Tag(127)], node[; Tag(128)], node[; Tag(129)],
+                let tag = 130; node[; Tag(tag)],
+                node[; Tag(131)], node[; Tag(132)], node[; Tag(133)],
+                node[; Tag(134)], node[; Tag(135)], node[; Tag(136)],
+                node[; Tag(137)], node[; Tag(138)], node[; Tag(139)],
+                let tag = 140; node[; Tag(tag)],
+            ) as root
+        };
+        commands.insert_resource(Root(root));
+    }
+    assert_eq!(children(setup), (1..=140).collect::<Vec<_>>());
+}
+
+#[test]
+fn ids() {
+    fn setup(mut commands: Commands) {
+        let node = NodeBundle::default();
+        let mounted: Vec<_> = (2..5).map(|tag| commands.spawn(Tag(tag)).id()).collect();
+        build_ui! {
+            #[cmd(commands)]
+            node(node[; Tag(1)], ids(mounted), node[; Tag(5)]) as root
+        };
+        commands.insert_resource(Root(root));
+    }
+    assert_eq!(children(setup), [1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn id() {
+    #[derive(Resource)]
+    struct Mounted(Entity);
+
+    fn setup(mut commands: Commands) {
+        let node = NodeBundle::default();
+        let mounted = commands.spawn(NodeBundle::default()).id();
+        build_ui! {
+            #[cmd(commands)]
+            node(
+                node[; Tag(1)],
+                id(mounted){width: 30 pct}[; Tag(2)](node[; Tag(4)]),
+                node[; Tag(3)],
+            ) as root
+        };
+        commands.insert_resource(Root(root));
+        commands.insert_resource(Mounted(mounted));
+    }
+    let mut app = App::new();
+    app.add_systems(Startup, setup);
+    app.update();
+    let world = app.world_mut();
+    // The entity reserving the place of `mounted` is gone
+    let tagged = world.query::<&Tag>().iter(world).count();
+    assert_eq!(world.entities().len() as usize, tagged + 1);
+    let root = world.resource::<Root>().0;
+    let mounted = world.resource::<Mounted>().0;
+    let tags = |parent: Entity| -> Vec<u32> {
+        let children = world.get::<Children>(parent).unwrap();
+        children
+            .iter()
+            .map(|e| world.get::<Tag>(*e).unwrap().0)
+            .collect()
+    };
+    assert_eq!(tags(root), [1, 2, 3]);
+    assert_eq!(tags(mounted), [4]);
+    assert_eq!(world.get::<Children>(root).unwrap()[1], mounted);
+    assert_eq!(
+        world.get::<Style>(mounted).unwrap().width,
+        Val::Percent(30.0)
+    );
+}
@@ -0,0 +1,5 @@
+//! Invalid macro inputs are reported with a `compile_error!`.
+#[test]
+fn compile_fail() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,46 @@
+//! `#[count(NAME)]` declares the number of entities spawned by the tree.
+use bevy::prelude::*;
+use bevy_ui_build_macros::*;
+
+fn entities(setup: fn(Commands)) -> usize {
+    let mut app = App::new();
+    app.add_systems(Startup, setup);
+    app.update();
+    app.world().entities().len() as usize
+}
+
+#[test]
+fn count() {
+    fn setup(mut commands: Commands) {
+        let node = NodeBundle::default();
+        build_ui! {
+            #[cmd(commands)]
+            #[count(MENU_SIZE)]
+            node(
+                let label = "Play";
+                node(node, text(label)),
+                repeat(i: 3) node(node),
+                batch(2) node,
+                (node.clone()) as last,
+            )
+        };
+        assert_eq!(MENU_SIZE, 1 + 3 + 3 * 2 + 2 + 1);
+        let _ = last;
+    }
+    assert_eq!(entities(setup), 13);
+}
+
+#[test]
+fn this_root() {
+    fn setup(mut commands: Commands) {
+        let square = NodeBundle::default();
+        let root = commands.spawn(NodeBundle::default()).id();
+        build_ui! {
+            #[cmd(commands.entity(root))]
+            #[count(CHILDREN)]
+            this(square)
+        };
+        assert_eq!(CHILDREN, 1);
+    }
+    assert_eq!(entities(setup), 2);
+}
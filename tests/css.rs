@@ -0,0 +1,42 @@
+//! Every keyword accepted by `css!` is a variant of the bevy enum it sets.
+use bevy::ui::*;
+use bevy_ui_build_macros::css;
+
+#[test]
+fn alignment_keywords() {
+    let style = css!("justify-items: start; justify-self: auto; align-content: stretch");
+    assert_eq!(style.justify_items, JustifyItems::Start);
+    assert_eq!(style.justify_self, JustifySelf::Auto);
+    assert_eq!(style.align_content, AlignContent::Stretch);
+
+    for _ in [
+        css!("align-items: start; align-items: end; align-items: flex-start; align-items: flex-end"),
+        css!("align-items: center; align-items: baseline; align-items: stretch"),
+        css!("align-self: auto; align-self: start; align-self: end; align-self: flex-start"),
+        css!("align-self: flex-end; align-self: center; align-self: baseline; align-self: stretch"),
+        css!("justify-items: start; justify-items: end; justify-items: center"),
+        css!("justify-items: baseline; justify-items: stretch"),
+        css!("justify-self: auto; justify-self: start; justify-self: end; justify-self: center"),
+        css!("justify-self: baseline; justify-self: stretch"),
+        css!("align-content: start; align-content: end; align-content: flex-start"),
+        css!("align-content: flex-end; align-content: center; align-content: stretch"),
+        css!("align-content: space-between; align-content: space-around; align-content: space-evenly"),
+        css!("justify-content: start; justify-content: end; justify-content: flex-start"),
+        css!("justify-content: flex-end; justify-content: center; justify-content: space-between"),
+        css!("justify-content: space-around; justify-content: space-evenly"),
+        css!("justify-content: stretch"),
+    ] {}
+}
+
+#[test]
+fn declarations() {
+    let style = css!("display: grid; width: 50%; margin: 4px 8px; flex: 1 0 auto");
+    assert_eq!(style.display, Display::Grid);
+    assert_eq!(style.width, Val::Percent(50.0));
+    assert_eq!(
+        style.margin,
+        UiRect::new(Val::Px(8.0), Val::Px(8.0), Val::Px(4.0), Val::Px(4.0))
+    );
+    assert_eq!(style.flex_grow, 1.0);
+    assert_eq!(style.flex_basis, Val::Auto);
+}
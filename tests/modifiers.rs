@@ -0,0 +1,121 @@
+//! The `build_ui!` style modifiers inserted as components.
+use bevy::a11y::{accesskit::Role, AccessibilityNode};
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+use bevy_ui_build_macros::*;
+
+fn spawn<M>(setup: impl IntoSystemConfigs<M>) -> App {
+    let mut app = App::new();
+    app.add_systems(Startup, setup);
+    app.update();
+    app
+}
+
+#[test]
+fn role_and_label() {
+    fn setup(mut commands: Commands) {
+        let vertical = NodeBundle::default();
+        let quit = String::from("Quit");
+        build_ui! {
+            #[cmd(commands)]
+            vertical{role: Button, label: "Start game"}(vertical{label: quit})
+        };
+    }
+    let mut app = spawn(setup);
+    let world = app.world_mut();
+    let mut nodes: Vec<_> = world
+        .query::<&AccessibilityNode>()
+        .iter(world)
+        .map(|node| node.0.clone().build())
+        .collect();
+    nodes.sort_by_key(|node| node.role() == Role::Unknown);
+    assert_eq!(nodes.len(), 2);
+    assert_eq!(
+        (nodes[0].role(), nodes[0].name()),
+        (Role::Button, Some("Start game"))
+    );
+    assert_eq!(
+        (nodes[1].role(), nodes[1].name()),
+        (Role::Unknown, Some("Quit"))
+    );
+}
+
+#[test]
+fn camera() {
+    fn setup(mut commands: Commands) {
+        let vertical = NodeBundle::default();
+        let camera = commands.spawn_empty().id();
+        build_ui! {
+            #[cmd(commands)]
+            #[camera(camera)]
+            vertical(vertical{camera: camera})
+        };
+    }
+    let mut app = spawn(setup);
+    let world = app.world_mut();
+    let cameras: Vec<_> = world.query::<&TargetCamera>().iter(world).collect();
+    assert_eq!(cameras.len(), 2);
+}
+
+#[test]
+fn overlay() {
+    fn setup(mut commands: Commands) {
+        let vertical = NodeBundle::default();
+        build_ui! {
+            #[cmd(commands)]
+            vertical(
+                overlay{background: Color::BLACK, margin: 1 px}(vertical),
+                overlay{z: global 5},
+            )
+        };
+    }
+    let mut app = spawn(setup);
+    let world = app.world_mut();
+    let mut overlays: Vec<_> = world
+        .query::<(&Style, &ZIndex)>()
+        .iter(world)
+        .filter(|(style, _)| style.position_type == PositionType::Absolute)
+        .map(|(style, z_index)| (style.margin, *z_index))
+        .collect();
+    overlays.sort_by_key(|(margin, _)| margin.left != Val::Px(1.0));
+    assert_eq!(overlays.len(), 2);
+    assert!(matches!(overlays[0], (margin, ZIndex::Global(100)) if margin == rect!(1 px)));
+    assert!(matches!(overlays[1].1, ZIndex::Global(5)));
+}
+
+#[test]
+fn button() {
+    fn setup(mut commands: Commands) {
+        let vertical = NodeBundle::default();
+        let button = ButtonBundle {
+            focus_policy: FocusPolicy::Pass,
+            ..Default::default()
+        };
+        build_ui! {
+            #[cmd(commands)]
+            vertical(button, batch(2) button)
+        };
+        let button = NodeBundle::default();
+        build_ui! {
+            #[cmd(commands)]
+            vertical(button, batch(2) button)
+        };
+    }
+    let mut app = spawn(setup);
+    let world = app.world_mut();
+    let mut policies: Vec<_> = world
+        .query_filtered::<&FocusPolicy, (With<Button>, With<Interaction>)>()
+        .iter(world)
+        .map(|policy| *policy == FocusPolicy::Block)
+        .collect();
+    policies.sort();
+    assert_eq!(policies, [false, false, false, true, true, true]);
+
+    let button = NodeBundle::default();
+    let spawned = build_ui! { #[world(world)] #[collect] button };
+    assert_eq!(
+        world.get::<FocusPolicy>(spawned[0]),
+        Some(&FocusPolicy::Block)
+    );
+    assert!(world.get::<Interaction>(spawned[0]).is_some());
+}
@@ -0,0 +1,72 @@
+//! `as name` declares the entities named in every kind of child.
+use bevy::prelude::*;
+use bevy_ui_build_macros::*;
+
+const NOT_SPAWNED: Entity = Entity::from_raw(u32::MAX);
+
+define_template! {
+    card(content: slot) {
+        (NodeBundle::default())(slot!(content))
+    }
+}
+
+/// Has exactly one field per name, `#[out(..)]` fails to compile otherwise.
+struct Names {
+    root: Entity,
+    nested: Entity,
+    in_if: Entity,
+    in_else: Entity,
+    in_match: Entity,
+    in_for: Entity,
+    in_repeat: Entity,
+    in_slot: Entity,
+    in_cfg: Entity,
+}
+
+#[test]
+fn names() {
+    fn setup(mut commands: Commands) {
+        let node = NodeBundle::default();
+        let names = build_ui! {
+            #[cmd(commands)]
+            #[out(Names)]
+            node(
+                node(node as nested),
+                if (true) { node as in_if } else { node as in_else },
+                match (2) { 1 => { node }, _ => { node as in_match } },
+                for i in (0..3) { node[; ZIndex::Local(i)] as in_for },
+                repeat(i: 2) node[; ZIndex::Local(10 + i)] as in_repeat,
+                card!()(node as in_slot),
+                #[cfg(all())] node as in_cfg,
+            ) as root
+        };
+        commands.insert_resource(Spawned(names));
+    }
+    let mut app = App::new();
+    app.add_systems(Startup, setup);
+    app.update();
+    let world = app.world();
+    let names = &world.resource::<Spawned>().0;
+    let z_index = |entity| match world.get::<ZIndex>(entity) {
+        Some(ZIndex::Local(z)) => *z,
+        _ => panic!("{entity:?} has no local ZIndex"),
+    };
+    for entity in [
+        names.root,
+        names.nested,
+        names.in_if,
+        names.in_match,
+        names.in_slot,
+        names.in_cfg,
+    ] {
+        assert!(world.get_entity(entity).is_some());
+    }
+    assert_eq!(names.in_else, NOT_SPAWNED);
+    assert_eq!(z_index(names.in_for), 2);
+    assert_eq!(z_index(names.in_repeat), 11);
+    let parent = |entity| world.get::<Parent>(entity).map(Parent::get);
+    assert_eq!(parent(names.nested).and_then(parent), Some(names.root));
+}
+
+#[derive(Resource)]
+struct Spawned(Names);
@@ -0,0 +1,104 @@
+//! The pointer observers of `build_ui!` need `bevy_picking`, which comes with
+//! bevy 0.15, they are checked against it.
+use bevy_015 as bevy;
+
+use bevy::picking::backend::HitData;
+use bevy::picking::events::{Click, Drag, Out, Over, Pointer};
+use bevy::picking::pointer::{Location, PointerButton, PointerId};
+use bevy::prelude::*;
+use bevy::render::camera::NormalizedRenderTarget;
+use bevy_ui_build_macros::*;
+use std::time::Duration;
+
+#[derive(Resource, Default)]
+struct Events(Vec<&'static str>);
+
+fn trigger<E: Clone + Reflect + std::fmt::Debug>(world: &mut World, target: Entity, event: E) {
+    let location = Location {
+        target: NormalizedRenderTarget::Image(Handle::default()),
+        position: Vec2::ZERO,
+    };
+    world.trigger_targets(
+        Pointer::new(target, PointerId::Mouse, location, event),
+        target,
+    );
+    world.flush();
+}
+
+#[test]
+fn observers() {
+    fn setup(mut commands: Commands) {
+        let node = Node::default();
+        build_ui! {
+            #[cmd(commands)]
+            node[;
+                on_click: |_, mut events: ResMut<Events>| events.0.push("click"),
+                on_over: |trigger, mut events: ResMut<Events>| {
+                    assert_eq!(trigger.event().hit.depth, 1.0);
+                    events.0.push("over")
+                },
+                on_out: |_, mut events: ResMut<Events>| events.0.push("out"),
+                on_drag: |trigger, mut commands| {
+                    let delta = trigger.event().delta;
+                    commands.insert_resource(Events(vec![if delta.x > 0.0 { "right" } else { "left" }]));
+                },
+            ] as button
+        };
+        commands.insert_resource(Target(button));
+    }
+    #[derive(Resource)]
+    struct Target(Entity);
+
+    let mut app = App::new();
+    app.init_resource::<Events>().add_systems(Startup, setup);
+    app.update();
+    let world = app.world_mut();
+    let button = world.resource::<Target>().0;
+    let hit = HitData::new(Entity::PLACEHOLDER, 1.0, None, None);
+    let click = Click {
+        button: PointerButton::Primary,
+        hit: hit.clone(),
+        duration: Duration::ZERO,
+    };
+    trigger(world, button, Over { hit: hit.clone() });
+    trigger(world, button, click);
+    trigger(world, button, Out { hit });
+    assert_eq!(world.resource::<Events>().0, ["over", "click", "out"]);
+    let drag = Drag {
+        button: PointerButton::Primary,
+        distance: Vec2::X,
+        delta: Vec2::X,
+    };
+    trigger(world, button, drag);
+    assert_eq!(world.resource::<Events>().0, ["right"]);
+}
+
+#[test]
+fn tooltip() {
+    fn setup(mut commands: Commands) {
+        let node = Node::default();
+        let hint = (Text::new("hint"), Node::default());
+        build_ui! {
+            #[cmd(commands)]
+            node[; tooltip(hint)] as button
+        };
+        commands.insert_resource(Target(button));
+    }
+    #[derive(Resource)]
+    struct Target(Entity);
+
+    let mut app = App::new();
+    app.add_systems(Startup, setup);
+    app.update();
+    let world = app.world_mut();
+    let button = world.resource::<Target>().0;
+    let tooltip = world.get::<Children>(button).unwrap()[0];
+    assert_eq!(world.get::<Text>(tooltip).unwrap().0, "hint");
+    let visibility = |world: &World| *world.get::<Visibility>(tooltip).unwrap();
+    assert_eq!(visibility(world), Visibility::Hidden);
+    let hit = HitData::new(Entity::PLACEHOLDER, 1.0, None, None);
+    trigger(world, button, Over { hit: hit.clone() });
+    assert_eq!(visibility(world), Visibility::Inherited);
+    trigger(world, button, Out { hit });
+    assert_eq!(visibility(world), Visibility::Hidden);
+}
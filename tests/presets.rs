@@ -0,0 +1,44 @@
+//! The `build_ui!` style modifiers of presets other than `NodeBundle`.
+use bevy::prelude::*;
+use bevy_ui_build_macros::*;
+
+#[test]
+fn bundles_with_style() {
+    fn setup(mut commands: Commands) {
+        let button = ButtonBundle::default();
+        build_ui! {
+            #[cmd(commands)]
+            button{width: 10 px}(button{style: {width: 20 px}, z_index: ZIndex::Local(2)})
+        };
+        // `ImageBundle` isn't `Clone`, it can only be used with `#[move]`
+        let icon = ImageBundle::default();
+        build_ui! {
+            #[cmd(commands)]
+            #[move]
+            icon{width: 30 px}
+        };
+    }
+    let mut app = App::new();
+    app.add_systems(Startup, setup);
+    app.update();
+    let world = app.world_mut();
+    let mut buttons: Vec<_> = world
+        .query_filtered::<(&Style, &ZIndex), With<Button>>()
+        .iter(world)
+        .map(|(style, z)| (style.width, *z))
+        .collect();
+    buttons.sort_by_key(|(_, z)| matches!(z, ZIndex::Local(0)));
+    assert_eq!(
+        buttons,
+        [
+            (Val::Px(20.0), ZIndex::Local(2)),
+            (Val::Px(10.0), ZIndex::Local(0))
+        ]
+    );
+    let images: Vec<_> = world
+        .query_filtered::<&Style, (With<UiImage>, Without<Button>)>()
+        .iter(world)
+        .map(|style| style.width)
+        .collect();
+    assert_eq!(images, [Val::Px(30.0)]);
+}
@@ -0,0 +1,38 @@
+//! Every `place_content` and `place_items` keyword is a variant of the enums
+//! it sets.
+use bevy::ui::*;
+use bevy_ui_build_macros::*;
+
+#[test]
+fn place_items_keywords() {
+    let style = style! { place_items: flex_start center };
+    assert_eq!(style.align_items, AlignItems::FlexStart);
+    assert_eq!(style.justify_items, JustifyItems::Center);
+    for _ in [
+        style! { place_items: start },
+        style! { place_items: end },
+        style! { place_items: center },
+        style! { place_items: baseline },
+        style! { place_items: stretch },
+        style! { place_items: flex_start start },
+        style! { place_items: flex_end end },
+    ] {}
+}
+
+#[test]
+fn place_content_keywords() {
+    let style = style! { place_content: stretch space_between };
+    assert_eq!(style.align_content, AlignContent::Stretch);
+    assert_eq!(style.justify_content, JustifyContent::SpaceBetween);
+    for _ in [
+        style! { place_content: start },
+        style! { place_content: end },
+        style! { place_content: flex_start },
+        style! { place_content: flex_end },
+        style! { place_content: center },
+        style! { place_content: stretch },
+        style! { place_content: space_between },
+        style! { place_content: space_around },
+        style! { place_content: space_evenly },
+    ] {}
+}
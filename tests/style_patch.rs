@@ -0,0 +1,24 @@
+//! `style_patch!` changes only the fields it names.
+use bevy::prelude::*;
+use bevy_ui_build_macros::*;
+
+#[test]
+fn apply_and_merge() {
+    let compact = false;
+    let highlight =
+        style_patch! { border: 2 px, row, if compact { width: 10 px } else { width: full } };
+    let mut style = style! { margin: 1 px, border: 4 px };
+    highlight.apply(&mut style);
+    assert_eq!(style.margin, rect!(1 px));
+    assert_eq!(style.border, rect!(2 px));
+    assert_eq!(style.flex_direction, FlexDirection::Row);
+    assert_eq!(style.width, Val::Percent(100.0));
+    let fields: Vec<_> = highlight.fields().collect();
+    assert_eq!(fields, ["border", "flex_direction", "width"]);
+
+    let mut patch = style_patch! { width: 5 px, height: 5 px };
+    patch.merge(&highlight);
+    patch.apply(&mut style);
+    assert_eq!(style.width, Val::Percent(100.0));
+    assert_eq!(style.height, Val::Px(5.0));
+}
@@ -0,0 +1,5 @@
+use bevy_ui_build_macros::*;
+
+fn main() {
+    let _ = calc!(50 pct - 10 px);
+}
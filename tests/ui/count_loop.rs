@@ -0,0 +1,15 @@
+use bevy::prelude::*;
+use bevy_ui_build_macros::*;
+
+fn setup(mut commands: Commands) {
+    let square = NodeBundle::default();
+    build_ui! {
+        #[cmd(commands)]
+        #[count(SQUARES)]
+        square(for _ in (0..3) { square })
+    };
+}
+
+fn main() {
+    App::new().add_systems(Startup, setup);
+}
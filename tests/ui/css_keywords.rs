@@ -0,0 +1,6 @@
+use bevy_ui_build_macros::css;
+
+fn main() {
+    let _ = css!("justify-items: flex-start");
+    let _ = css!("justify-self: flex-end");
+}
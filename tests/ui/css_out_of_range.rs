@@ -0,0 +1,6 @@
+use bevy_ui_build_macros::css;
+
+fn main() {
+    let _ = css!("width: 1e40px");
+    let _ = css!("flex-grow: inf");
+}
@@ -0,0 +1,6 @@
+use bevy_ui_build_macros::*;
+
+fn main() {
+    let _ = style! { place_items: flex_start };
+    let _ = style! { place_content: baseline };
+}
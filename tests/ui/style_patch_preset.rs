@@ -0,0 +1,9 @@
+use bevy_ui_build_macros::*;
+
+fn card() -> bevy::ui::Style {
+    style! { padding: 8 px }
+}
+
+fn main() {
+    let _ = style_patch! { card, width: 10 px };
+}
@@ -0,0 +1,14 @@
+use bevy::prelude::*;
+use bevy_ui_build_macros::*;
+
+fn setup(mut commands: Commands) {
+    let button = NodeBundle::default();
+    build_ui! {
+        #[cmd(commands)]
+        button[; tooltip(text("Reload weapon"))]
+    };
+}
+
+fn main() {
+    App::new().add_systems(Startup, setup);
+}
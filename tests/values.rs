@@ -0,0 +1,30 @@
+//! The value macros, `unit!`, `size!`, `rect!` and `calc!`.
+use bevy::ui::{UiRect, Val};
+use bevy_ui_build_macros::*;
+
+const MARGIN: UiRect = rect!(4 px);
+const INDENT: UiRect = rect!(left: 16 px);
+const GUTTER: UiRect = rect!(horizontal 8 px);
+const CARD: UiRect = rect!(x: 4 px, top: 2 px, ..0);
+
+#[test]
+fn named_sides_default_to_auto() {
+    assert_eq!(MARGIN, UiRect::all(Val::Px(4.0)));
+    assert_eq!(INDENT.left, Val::Px(16.0));
+    assert_eq!(INDENT.top, Val::Auto);
+    assert_eq!(GUTTER.right, Val::Px(8.0));
+    assert_eq!(GUTTER.bottom, Val::Auto);
+    assert_eq!(CARD.top, Val::Px(2.0));
+    assert_eq!(CARD.bottom, Val::Px(0.0));
+}
+
+#[test]
+fn calc_terms_are_expressions() {
+    let width = 10.0;
+    assert_eq!(calc!(50 pct - 10 pct), Val::Percent(40.0));
+    assert_eq!(calc!(-5 px + 3 px), Val::Px(-2.0));
+    assert_eq!(calc!(width * 2.0 px - width px), Val::Px(10.0));
+    assert_eq!(calc!(width - 4.0 vw + 1 vw), Val::Vw(7.0));
+    assert_eq!(calc!(50 pct - 10 px, of: 200.0), Val::Px(90.0));
+    assert_eq!(calc!(-width px + 10 pct, of: width * 10.0), Val::Px(0.0));
+}
@@ -0,0 +1,42 @@
+//! `#[world(..)]` spawns the tree directly in a `World`.
+use bevy::prelude::*;
+use bevy_ui_build_macros::*;
+
+#[derive(Component, Clone)]
+struct Tag(u32);
+
+#[test]
+fn spawn_in_world() {
+    let mut world = World::new();
+    let world = &mut world;
+    let node = NodeBundle::default();
+    build_ui! {
+        #[world(world)]
+        node(
+            text("label") as label,
+            repeat(i: 2) node[; Tag(i + 1)],
+            for i in (3..5) { node[; Tag(i)] },
+        ) as root
+    };
+    let tags: Vec<_> = world
+        .get::<Children>(root)
+        .unwrap()
+        .iter()
+        .map(|child| world.get::<Tag>(*child).map_or(0, |tag| tag.0))
+        .collect();
+    assert_eq!(tags, [0, 1, 2, 3, 4]);
+    assert!(world.get::<Text>(label).is_some());
+
+    let spawned = build_ui! {
+        #[world(world)]
+        #[collect]
+        #[parent(root)]
+        node(node)
+    };
+    assert_eq!(spawned.len(), 2);
+    assert_eq!(world.get::<Parent>(spawned[0]).map(Parent::get), Some(root));
+    assert_eq!(
+        world.get::<Parent>(spawned[1]).map(Parent::get),
+        Some(spawned[0])
+    );
+}